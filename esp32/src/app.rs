@@ -3,13 +3,14 @@ use manrf::event::{Button, Event};
 use manrf::utils::EdgeInsets;
 use manrf::{elements::*, palette::PALETTE_DREAM};
 const BORDERED_STYLE: StyleDefinition = StyleDefinition {
-    background: Some(PALETTE_DREAM.darkest),
+    background: Some(Background::Color(PALETTE_DREAM.darkest)),
     margin: EdgeInsets::symmetric(2, 4),
     border: BorderDefinition::new(PALETTE_DREAM.dark, EdgeInsets::new(1, 2, 3, 4)),
     padding: EdgeInsets::all(2),
+    shadow: None,
 };
 
-use manrf::{ItemSelector, ItemSelectorState, Stack, Text};
+use manrf::{lens, ItemSelector, ItemSelectorState, Stack, Text};
 
 #[derive(Clone)]
 struct Key {
@@ -55,7 +56,7 @@ impl Default for AppState {
     }
 }
 
-static GO_BACK: EventHandler<AppState> = |state, event| {
+pub static GO_BACK: EventHandler<AppState> = |state, event| {
     if let Event::ButtonPressed(Button::Back) = event {
         state.keys_selected_state.selected = None;
         true
@@ -66,16 +67,15 @@ static GO_BACK: EventHandler<AppState> = |state, event| {
 
 fn item_selector_view(_state: &AppState) -> Element<AppState> {
     ItemSelector::<AppState, Key>::new(
-        |state| &state.keys,
-        |state| state.keys_selected_state.clone(),
-        |state, new_state| state.keys_selected_state = new_state,
+        lens!(AppState, keys),
+        lens!(AppState, keys_selected_state),
         |key: &Key, selected: bool| {
             Style::new_with_style(
                 StyleDefinition {
                     background: if selected {
-                        Some(PALETTE_DREAM.light)
+                        Some(Background::Color(PALETTE_DREAM.light))
                     } else {
-                        Some(PALETTE_DREAM.darkest)
+                        Some(Background::Color(PALETTE_DREAM.darkest))
                     },
                     ..BORDERED_STYLE
                 },
@@ -92,18 +92,15 @@ fn selected_view(state: &AppState) -> Element<AppState> {
         .map(|index| &state.keys[index])
         .unwrap_or(&Key::default())
         .clone();
-    Handler::new(
-        GO_BACK,
-        border(
-            BorderDefinition {
-                color: PALETTE_DREAM.darkest,
-                size: EdgeInsets::all(2),
-            },
-            Text::new(format!(
-                "Selected: {} {}",
-                selected_key.text, selected_key.key
-            )),
-        ),
+    border(
+        BorderDefinition {
+            color: PALETTE_DREAM.darkest,
+            size: EdgeInsets::all(2),
+        },
+        Text::new(format!(
+            "Selected: {} {}",
+            selected_key.text, selected_key.key
+        )),
     )
 }
 
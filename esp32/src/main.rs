@@ -1,11 +1,12 @@
 mod app;
 
 use esp_idf_sys as _;
+use manrf::event::{Button, DebouncedButton, InputSource, Key, Phase};
 use manrf::graphics::EmbeddedGraphicsEndpoint;
 use manrf::{defs::Runner, App}; // If using the `binstart` feature of `esp-idf-sys`, always keep this module imported
 
 use std::thread;
-use std::time::Duration;
+use std::time::{Duration, Instant};
 
 use embedded_hal::spi::MODE_3;
 
@@ -18,7 +19,7 @@ use esp_idf_hal::units::FromValueType;
 use display_interface_spi::SPIInterfaceNoCS;
 
 use embedded_graphics::image::*;
-use embedded_graphics::pixelcolor::{Rgb565, Rgb888};
+use embedded_graphics::pixelcolor::Rgb565;
 use embedded_graphics::prelude::*;
 
 use mipidsi::Builder;
@@ -36,6 +37,18 @@ fn main() {
     let sclk = peripherals.pins.gpio18; //ok
     let cs = peripherals.pins.gpio5; //ok
 
+    // the two buttons of the board, pulled up
+    let mut buttons: Vec<Box<dyn InputSource>> = vec![
+        Box::new(DebouncedButton::new(
+            PinDriver::input(peripherals.pins.gpio0).unwrap(),
+            Key::Button(Button::Principal),
+        )),
+        Box::new(DebouncedButton::new(
+            PinDriver::input(peripherals.pins.gpio35).unwrap(),
+            Key::Button(Button::Back),
+        )),
+    ];
+
     let mut delay = Ets;
 
     // configuring the spi interface, note that in order for the ST7789 to work, the data_mode needs to be set to MODE_3
@@ -64,19 +77,25 @@ fn main() {
     display.clear(Rgb565::RED).unwrap();
     //ferris.draw(&mut display).unwrap();
 
-    let converted_display = display.color_converted::<Rgb888>();
-
-    let endpoint = EmbeddedGraphicsEndpoint::new(converted_display);
+    let endpoint = EmbeddedGraphicsEndpoint::new(display);
 
     let mut app = App::new(
         app::main_menu,
         embedded_graphics::geometry::Size::new(135, 240),
         endpoint,
     );
+    app.add_shortcut(Phase::Bubble, app::GO_BACK);
+    // every pixel saved is time saved on the SPI bus
+    app.set_optimize(true);
 
+    let started = Instant::now();
     loop {
+        let now = started.elapsed();
+        app.poll_source(&mut buttons, now);
+        app.tick(now);
+        // only sends a frame when the events or the ticks changed something
         app.draw();
-        thread::sleep(Duration::from_millis(1000));
+        thread::sleep(Duration::from_millis(10));
         // if backlight.is_set_high() {
         //     backlight.set_low().unwrap();
         // } else {
@@ -0,0 +1,152 @@
+//! Values kept from one render of an app to the next, so memoized elements
+//! can reuse what they built when their inputs didn't change.
+
+use core::any::{Any, TypeId};
+use core::hash::{Hash, Hasher};
+use std::cell::RefCell;
+use std::collections::hash_map::DefaultHasher;
+use std::collections::HashMap;
+
+struct Entry {
+    inputs: Box<dyn Any>,
+    value: Box<dyn Any>,
+    used: bool,
+}
+
+/// Cache owned by the app, available to the elements while it renders.
+#[derive(Default)]
+pub(crate) struct RenderCache {
+    entries: HashMap<TypeId, Vec<Entry>>,
+}
+
+impl RenderCache {
+    fn get<I: PartialEq + 'static, T: Clone + 'static>(
+        &mut self,
+        kind: TypeId,
+        inputs: &I,
+    ) -> Option<T> {
+        // an entry goes to a single element per render, even when several
+        // have equal inputs
+        let entry = self.entries.get_mut(&kind)?.iter_mut().find(|entry| {
+            !entry.used && entry.inputs.downcast_ref::<I>() == Some(inputs) && entry.value.is::<T>()
+        })?;
+        entry.used = true;
+        entry.value.downcast_ref::<T>().cloned()
+    }
+
+    fn insert<I: 'static, T: 'static>(&mut self, kind: TypeId, inputs: I, value: T) {
+        self.entries.entry(kind).or_default().push(Entry {
+            inputs: Box::new(inputs),
+            value: Box::new(value),
+            used: true,
+        });
+    }
+
+    /// Drops the entries the last render didn't use.
+    fn evict_unused(&mut self) {
+        for entries in self.entries.values_mut() {
+            entries.retain_mut(|entry| core::mem::replace(&mut entry.used, false));
+        }
+        self.entries.retain(|_, entries| !entries.is_empty());
+    }
+}
+
+thread_local! {
+    static CURRENT: RefCell<Option<RenderCache>> = const { RefCell::new(None) };
+}
+
+/// Makes the cache available to [`cached`] while `render` runs, then evicts
+/// what it didn't use.
+pub(crate) fn with_cache<R>(cache: &mut RenderCache, render: impl FnOnce() -> R) -> R {
+    let previous = CURRENT.with(|current| current.replace(Some(core::mem::take(cache))));
+    let result = render();
+    *cache = CURRENT
+        .with(|current| current.replace(previous))
+        .unwrap_or_default();
+    cache.evict_unused();
+    result
+}
+
+/// Identifier of a memoized element, from the key its caller gave it.
+pub(crate) fn instance_key(key: impl Hash) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    key.hash(&mut hasher);
+    hasher.finish()
+}
+
+/// The value made for equal `inputs` by the same kind of element in the
+/// previous render, or a new one from `make`.
+///
+/// Outside the render of an app, values are always made again.
+pub(crate) fn cached<I, T>(kind: TypeId, inputs: &I, make: impl FnOnce() -> T) -> T
+where
+    I: PartialEq + Clone + 'static,
+    T: Clone + 'static,
+{
+    let hit = CURRENT.with(|current| {
+        current
+            .borrow_mut()
+            .as_mut()
+            .and_then(|cache| cache.get(kind, inputs))
+    });
+    if let Some(value) = hit {
+        return value;
+    }
+    // `make` can render memoized elements too, so the cache isn't borrowed
+    let value = make();
+    CURRENT.with(|current| {
+        if let Some(cache) = current.borrow_mut().as_mut() {
+            cache.insert(kind, inputs.clone(), value.clone());
+        }
+    });
+    value
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    struct Kind;
+
+    fn double(value: &u32, calls: &mut u32) -> u32 {
+        cached(TypeId::of::<Kind>(), value, || {
+            *calls += 1;
+            value * 2
+        })
+    }
+
+    #[test]
+    fn values_are_reused_by_the_next_render_only() {
+        let mut cache = RenderCache::default();
+        let mut calls = 0;
+        with_cache(&mut cache, || {
+            assert_eq!(double(&1, &mut calls), 2);
+            assert_eq!(double(&2, &mut calls), 4);
+        });
+        with_cache(&mut cache, || assert_eq!(double(&1, &mut calls), 2));
+        assert_eq!(calls, 2);
+
+        // 2 wasn't used by the last render
+        with_cache(&mut cache, || assert_eq!(double(&2, &mut calls), 4));
+        assert_eq!(calls, 3);
+
+        assert_eq!(double(&2, &mut calls), 4);
+        assert_eq!(calls, 4);
+    }
+
+    #[test]
+    fn entries_go_to_one_element_per_render() {
+        let mut cache = RenderCache::default();
+        let mut calls = 0;
+        with_cache(&mut cache, || {
+            double(&1, &mut calls);
+            double(&1, &mut calls);
+        });
+        assert_eq!(calls, 2);
+        with_cache(&mut cache, || {
+            double(&1, &mut calls);
+            double(&1, &mut calls);
+        });
+        assert_eq!(calls, 2);
+    }
+}
@@ -1,6 +1,14 @@
-use crate::{utils::*, graphics::GraphicOperationQueue};
+use core::time::Duration;
 
-pub trait State: Default + 'static {}
+use crate::{utils::*, graphics::GraphicOperationQueue, event::Timers};
+
+pub trait State: Default + 'static {
+    /// Timers of the state, fired by `App::tick`. States scheduling timers
+    /// keep them in a field and return it here.
+    fn timers(&mut self) -> Option<&mut Timers> {
+        None
+    }
+}
 
 pub trait ElementTrait<S: State>{
     fn to_string(&self) -> String {
@@ -8,18 +16,61 @@ pub trait ElementTrait<S: State>{
     }
     fn render(&self, constraints: Constraints, state: &S) -> (Size, RenderNode<S>);
     fn paint(&self, _size: Size, _pos: Point, _display: &mut GraphicOperationQueue) {}
-    fn event_handler(&self, _state: &mut S, _event: Event) -> bool {
+    /// Paints the element, then `child`, the node it rendered under it.
+    /// Elements replaying what they painted before override it, and the
+    /// elements wrapping another one forward it.
+    fn paint_with_child(
+        &self,
+        size: Size,
+        pos: Point,
+        child: &RenderNode<S>,
+        display: &mut GraphicOperationQueue,
+    ) {
+        self.paint(size, pos, display);
+        child.paint(display, pos);
+    }
+    /// Whether the element can hold the focus, receiving key events.
+    fn focusable(&self) -> bool {
         false
     }
+    /// Identifies the content under the element, like the route a router
+    /// shows. When it changes, the app saves the focus of the old content
+    /// and restores the one saved for the new content, or focuses its first
+    /// element.
+    fn focus_key(&self) -> Option<u64> {
+        None
+    }
+    /// Whether the element gets the `Tick` sent on every tick of the app, to
+    /// animate or show the time. Other elements never get it.
+    fn wants_ticks(&self) -> bool {
+        false
+    }
+    /// Handles the event when the element is its target, or while bubbling
+    /// up from a descendant.
+    fn event_handler(&self, _state: &mut S, _event: Event) -> EventResponse {
+        EventResponse::Ignored
+    }
+    /// Handles the event while it travels down to the target, before the
+    /// target and the elements between them.
+    fn capture_event_handler(&self, _state: &mut S, _event: Event) -> EventResponse {
+        EventResponse::Ignored
+    }
 }
 
 pub type Element<S> = Rc<dyn ElementTrait<S>>;
 
+#[deprecated(note = "handlers take closures, see `Handler::new`")]
 pub type EventFunction<S> = fn(&mut S, Event) -> bool;
 
+#[deprecated(note = "generators take closures, see `Component::new`")]
 pub type ComponentGenerator<S> = fn(&S) -> Element<S>;
 
-#[derive(Clone)]
+/// Event handler function or closure, as stored by the elements.
+pub(crate) type SharedEventHandler<S> = Rc<dyn Fn(&mut S, Event) -> EventResponse>;
+
+/// Generator function or closure, as stored by the elements.
+pub(crate) type SharedGenerator<S> = Rc<dyn Fn(&S) -> Element<S>>;
+
 pub enum RenderNode<S> {
     SingleChild {
         offset: Point,
@@ -34,9 +85,63 @@ pub enum RenderNode<S> {
     },
     Leaf,
 }
+
+// not derived, which would require `S: Clone`
+impl<S> Clone for RenderNode<S> {
+    fn clone(&self) -> Self {
+        match self {
+            RenderNode::SingleChild {
+                offset,
+                size,
+                renderer,
+                child,
+            } => RenderNode::SingleChild {
+                offset: *offset,
+                size: *size,
+                renderer: renderer.clone(),
+                child: child.clone(),
+            },
+            RenderNode::MultiChild {
+                offset,
+                size,
+                child,
+            } => RenderNode::MultiChild {
+                offset: *offset,
+                size: *size,
+                child: child.clone(),
+            },
+            RenderNode::Leaf => RenderNode::Leaf,
+        }
+    }
+}
+
+impl<S: State> RenderNode<S> {
+    /// Paints the elements of the tree, with `origin` as its position.
+    pub fn paint(&self, display: &mut GraphicOperationQueue, origin: Point) {
+        match self {
+            RenderNode::SingleChild {
+                offset,
+                size,
+                renderer,
+                child,
+            } => renderer.paint_with_child(*size, origin + *offset, child, display),
+            RenderNode::MultiChild { offset, child, .. } => {
+                for item in child {
+                    item.paint(display, origin + *offset);
+                }
+            }
+            RenderNode::Leaf => {}
+        }
+    }
+}
 pub trait Runner {
     #[deprecated]
     fn to_string(&mut self) -> String;
     fn handle_event(&mut self, event: crate::event::Event);
+    /// Advances the time of the app to `now`, since any fixed instant.
+    /// Runners without timed behavior can ignore it.
+    fn tick(&mut self, _now: Duration) {}
+    /// Draws the app when it changed since the last draw, so it can be
+    /// called on every iteration of the main loop.
     fn draw(&mut self);
 }
\ No newline at end of file
@@ -1,5 +1,6 @@
 use crate::{
     defs::{Element, State},
+    graphics::LinearGradient,
     utils::EdgeInsets,
 };
 use embedded_graphics::{pixelcolor::Rgb888};
@@ -7,11 +8,17 @@ use embedded_graphics::{pixelcolor::Rgb888};
 mod align;
 mod component;
 mod handler;
+mod memo;
+mod router;
+mod scope;
 mod style;
 
 pub use align::*;
 pub use component::*;
 pub use handler::*;
+pub use memo::*;
+pub use router::*;
+pub use scope::*;
 pub use style::*;
 
 pub fn border<S: State>(border: BorderDefinition, child: Element<S>) -> Element<S> {
@@ -38,6 +45,23 @@ pub fn background<S: State>(background: Rgb888, child: Element<S>) -> Element<S>
     )
 }
 
+pub fn gradient<S: State>(gradient: LinearGradient, child: Element<S>) -> Element<S> {
+    Style::new_with_background(Background::Gradient(gradient), child)
+}
+
+pub fn shadow<S: State>(shadow: Shadow, child: Element<S>) -> Element<S> {
+    Style::new_with_style(
+        StyleDefinition {
+            background: None,
+            margin: EdgeInsets::all(0),
+            border: BorderDefinition::none(),
+            padding: EdgeInsets::all(0),
+            shadow: Some(shadow),
+        },
+        child,
+    )
+}
+
 pub fn center<S: State>(child: Element<S>) -> Element<S> {
     Align::new(
         Alignment2D {
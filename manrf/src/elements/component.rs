@@ -1,34 +1,211 @@
+use core::any::TypeId;
+use core::hash::Hash;
+
+use crate::cache::{cached, instance_key};
 use crate::defs::*;
 use crate::utils::*;
 
+/// Generator defined as a function. Components can also be generated by
+/// closures capturing their parameters.
 pub type Generator<S> = fn(&S) -> Element<S>;
 
 pub struct Component<S> {
-    generator: Generator<S>,
+    generator: SharedGenerator<S>,
 }
 
 impl<S: State> Component<S> {
-    pub fn new(generator: Generator<S>) -> Rc<Self> {
-        Rc::new(Self { generator })
+    pub fn new(generator: impl Fn(&S) -> Element<S> + 'static) -> Rc<Self> {
+        Rc::new(Self {
+            generator: Rc::new(generator),
+        })
+    }
+
+    /// A component generated from its props and the state, so the same
+    /// generator can be reused with different parameters.
+    pub fn with_props<P: 'static>(
+        props: P,
+        generator: impl Fn(&P, &S) -> Element<S> + 'static,
+    ) -> Rc<PropsComponent<P, S>> {
+        Rc::new(PropsComponent {
+            props,
+            generator: Rc::new(generator),
+        })
+    }
+
+    /// A component with props that is only generated again when its props
+    /// differ from the ones it had in the previous render. Memo components
+    /// are told apart by their generator and `key`, so the ones made by the
+    /// same code, like the rows of a list, need different keys, like the
+    /// index of their item.
+    ///
+    /// The generator only gets the props, so everything the element depends
+    /// on has to be in them.
+    pub fn memo<P, G>(key: impl Hash, props: P, generator: G) -> Rc<PropsComponent<P, S>>
+    where
+        P: PartialEq + Clone + 'static,
+        G: Fn(&P) -> Element<S> + 'static,
+    {
+        let key = instance_key(key);
+        Self::with_props(props, move |props: &P, _: &S| {
+            cached(TypeId::of::<G>(), &(key, props.clone()), || {
+                generator(props)
+            })
+        })
     }
 }
 
 impl<S: State> ElementTrait<S> for Component<S> {
     fn render(&self, constraints: Constraints, state: &S) -> (Size, RenderNode<S>) {
-        let child: Rc<dyn ElementTrait<S>> = (self.generator)(state);
-        let (size, child_node) = child.render(constraints, state);
-        (
+        render_generated((self.generator)(state), constraints, state)
+    }
+}
+
+type PropsGenerator<P, S> = Rc<dyn Fn(&P, &S) -> Element<S>>;
+
+pub struct PropsComponent<P, S> {
+    props: P,
+    generator: PropsGenerator<P, S>,
+}
+
+impl<P: 'static, S: State> ElementTrait<S> for PropsComponent<P, S> {
+    fn render(&self, constraints: Constraints, state: &S) -> (Size, RenderNode<S>) {
+        render_generated((self.generator)(&self.props, state), constraints, state)
+    }
+}
+
+fn render_generated<S: State>(
+    child: Element<S>,
+    constraints: Constraints,
+    state: &S,
+) -> (Size, RenderNode<S>) {
+    let (size, child_node) = child.render(constraints, state);
+    (
+        size,
+        RenderNode::SingleChild {
+            offset: Point::default(),
             size,
-            RenderNode::SingleChild {
-                offset: Point::default(),
-                size,
-                renderer: child.clone(),
-                child: Box::new(child_node),
+            renderer: child.clone(),
+            child: Box::new(child_node),
+        },
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::elements::Handler;
+    use crate::graphics::RecordingEndpoint;
+    use crate::{App, Runner, Stack, Text};
+    use std::cell::Cell;
+
+    #[derive(Default)]
+    struct Counter {
+        value: u32,
+        unrelated: u32,
+    }
+
+    impl State for Counter {}
+
+    fn update(state: &mut Counter, event: Event) -> bool {
+        match event {
+            Event::DirectionPressed(Direction::Up) => state.value += 1,
+            Event::DirectionPressed(Direction::Down) => state.unrelated += 1,
+            _ => return false,
+        }
+        true
+    }
+
+    fn labeled(label: &'static str) -> Element<Counter> {
+        Component::with_props(label, |label, state: &Counter| {
+            Text::new(format!("{}: {}", label, state.value))
+        })
+    }
+
+    #[test]
+    fn props_parameterize_the_generator() {
+        let mut app = App::new(
+            |_: &Counter| {
+                Handler::new(
+                    update,
+                    Stack::col(vec![labeled("Temperature"), labeled("Humidity")]),
+                )
             },
-        )
+            Size::new(200, 100),
+            RecordingEndpoint::new(),
+        );
+        app.handle_event(Event::DirectionPressed(Direction::Up));
+        app.draw();
+        assert!(app.endpoint.find_text("Temperature: 1").is_some());
+        assert!(app.endpoint.find_text("Humidity: 1").is_some());
     }
 
-    fn event_handler(&self, state: &mut S, event: Event) -> bool {
-        (self.generator)(state).event_handler(state, event)
+    #[test]
+    fn memo_components_are_generated_when_props_change() {
+        let generated = Rc::new(Cell::new(0));
+        let counter = generated.clone();
+        let mut app = App::new(
+            move |state: &Counter| {
+                let counter = counter.clone();
+                Handler::new(
+                    update,
+                    Component::memo("value", state.value, move |value| {
+                        counter.set(counter.get() + 1);
+                        Text::new(format!("Value {}", value))
+                    }),
+                )
+            },
+            Size::new(100, 100),
+            RecordingEndpoint::new(),
+        );
+        assert_eq!(generated.get(), 1);
+
+        app.handle_event(Event::DirectionPressed(Direction::Down));
+        assert_eq!(generated.get(), 1);
+
+        app.handle_event(Event::DirectionPressed(Direction::Up));
+        assert_eq!(generated.get(), 2);
+        app.draw();
+        assert!(app.endpoint.find_text("Value 1").is_some());
+    }
+
+    #[test]
+    fn memo_components_of_the_same_generator_are_kept_apart() {
+        let mut app = App::new(
+            |state: &Counter| {
+                let rows = ["Min", "Max"]
+                    .into_iter()
+                    .map(|label| {
+                        Component::memo(label, state.value, move |value| {
+                            Text::new(format!("{} {}", label, value))
+                        }) as Element<Counter>
+                    })
+                    .collect();
+                Handler::new(update, Stack::col(rows))
+            },
+            Size::new(100, 100),
+            RecordingEndpoint::new(),
+        );
+        app.handle_event(Event::DirectionPressed(Direction::Up));
+        app.draw();
+        assert!(app.endpoint.find_text("Min 1").is_some());
+        assert!(app.endpoint.find_text("Max 1").is_some());
+    }
+
+    #[test]
+    fn memo_components_with_the_same_key_keep_their_own_generator() {
+        let mut app = App::new(
+            |state: &Counter| {
+                let component: Element<Counter> = match state.value {
+                    0 => Component::memo(0, (), |_| Text::new("Before".to_string())),
+                    _ => Component::memo(0, (), |_| Text::new("After".to_string())),
+                };
+                Handler::new(update, component)
+            },
+            Size::new(100, 100),
+            RecordingEndpoint::new(),
+        );
+        app.handle_event(Event::DirectionPressed(Direction::Up));
+        app.draw();
+        assert!(app.endpoint.find_text("After").is_some());
     }
 }
@@ -1,16 +1,65 @@
 use crate::defs::*;
 use crate::utils::*;
 
+/// Handler defined as a function, for `static` handlers. Handlers can also
+/// be closures capturing their context.
 pub type EventHandler<S> = fn(&mut S, Event) -> bool;
 
 pub struct Handler<S> {
-    handler: EventHandler<S>,
+    handler: SharedEventHandler<S>,
+    phase: Phase,
+    ticks: bool,
     child: Element<S>,
 }
 
 impl<S: State> Handler<S> {
-    pub fn new(handler: EventHandler<S>, child: Element<S>) -> Rc<Self> {
-        Rc::new(Self { handler, child })
+    /// Handles the events its descendants ignore.
+    pub fn new<R: Into<EventResponse>>(
+        handler: impl Fn(&mut S, Event) -> R + 'static,
+        child: Element<S>,
+    ) -> Rc<Self> {
+        Self::with_phase(Phase::Bubble, handler, child)
+    }
+
+    /// Handles the events its descendants ignore, and the `Tick` of every
+    /// tick of the app.
+    pub fn ticking<R: Into<EventResponse>>(
+        handler: impl Fn(&mut S, Event) -> R + 'static,
+        child: Element<S>,
+    ) -> Rc<Self> {
+        Self::build(Phase::Bubble, true, handler, child)
+    }
+
+    /// Handles the events before its descendants get them.
+    pub fn capture<R: Into<EventResponse>>(
+        handler: impl Fn(&mut S, Event) -> R + 'static,
+        child: Element<S>,
+    ) -> Rc<Self> {
+        Self::with_phase(Phase::Capture, handler, child)
+    }
+
+    /// Handlers return a `bool`, `true` when they handled the event and
+    /// changed the state, or an [`EventResponse`].
+    pub fn with_phase<R: Into<EventResponse>>(
+        phase: Phase,
+        handler: impl Fn(&mut S, Event) -> R + 'static,
+        child: Element<S>,
+    ) -> Rc<Self> {
+        Self::build(phase, false, handler, child)
+    }
+
+    fn build<R: Into<EventResponse>>(
+        phase: Phase,
+        ticks: bool,
+        handler: impl Fn(&mut S, Event) -> R + 'static,
+        child: Element<S>,
+    ) -> Rc<Self> {
+        Rc::new(Self {
+            handler: Rc::new(move |state: &mut S, event| handler(state, event).into()),
+            phase,
+            ticks,
+            child,
+        })
     }
 }
 
@@ -28,7 +77,161 @@ impl<S: State> ElementTrait<S> for Handler<S> {
         )
     }
 
-    fn event_handler(&self, state: &mut S, event: Event) -> bool {
-        (self.handler)(state, event)
+    fn wants_ticks(&self) -> bool {
+        self.ticks
+    }
+
+    fn event_handler(&self, state: &mut S, event: Event) -> EventResponse {
+        match self.phase {
+            Phase::Bubble => (self.handler)(state, event),
+            Phase::Capture => EventResponse::Ignored,
+        }
+    }
+
+    fn capture_event_handler(&self, state: &mut S, event: Event) -> EventResponse {
+        match self.phase {
+            Phase::Capture => (self.handler)(state, event),
+            Phase::Bubble => EventResponse::Ignored,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::graphics::RecordingEndpoint;
+    use crate::{lens, App, ItemSelector, ItemSelectorState, Runner, Text};
+
+    struct ListState {
+        items: Vec<&'static str>,
+        selector: ItemSelectorState,
+        handled: Vec<&'static str>,
+    }
+
+    impl Default for ListState {
+        fn default() -> Self {
+            Self {
+                items: vec!["a", "b"],
+                selector: ItemSelectorState::default(),
+                handled: Vec::new(),
+            }
+        }
+    }
+
+    impl State for ListState {}
+
+    fn list() -> Element<ListState> {
+        ItemSelector::new(
+            lens!(ListState, items),
+            lens!(ListState, selector),
+            |text: &&'static str, _| Text::new(text.to_string()),
+        )
+    }
+
+    fn app(
+        root: impl Fn(&ListState) -> Element<ListState> + 'static,
+    ) -> App<ListState, RecordingEndpoint> {
+        App::new(root, Size::new(100, 100), RecordingEndpoint::new())
+    }
+
+    fn record(state: &mut ListState, event: Event) -> bool {
+        match event {
+            Event::ButtonPressed(Button::Principal) => {
+                state.handled.push("principal");
+                true
+            }
+            Event::DirectionPressed(Direction::Down) => {
+                state.handled.push("down");
+                true
+            }
+            _ => false,
+        }
+    }
+
+    #[test]
+    fn capture_handlers_run_before_the_target() {
+        let mut app = app(|_| Handler::capture(record, list()));
+        app.handle_event(Event::ButtonPressed(Button::Principal));
+        assert_eq!(app.state().handled, vec!["principal"]);
+        assert_eq!(app.state().selector.selected, None);
+    }
+
+    #[test]
+    fn bubble_handlers_run_when_the_target_ignores_the_event() {
+        let mut app = app(|_| Handler::new(record, list()));
+        app.handle_event(Event::ButtonPressed(Button::Principal));
+        assert!(app.state().handled.is_empty());
+        assert_eq!(app.state().selector.selected, Some(0));
+
+        app.handle_event(Event::DirectionPressed(Direction::Down));
+        assert!(app.state().handled.is_empty());
+        app.handle_event(Event::DirectionPressed(Direction::Down));
+        assert_eq!(app.state().handled, vec!["down"]);
+    }
+
+    #[test]
+    fn without_focus_handlers_bubble_from_the_deepest() {
+        let mut app = app(|_| {
+            Handler::new(
+                |state: &mut ListState, _| {
+                    state.handled.push("outer");
+                    true
+                },
+                Handler::new(
+                    |state: &mut ListState, _| {
+                        state.handled.push("inner");
+                        true
+                    },
+                    Text::new("no focusable element".to_string()),
+                ),
+            )
+        });
+        app.handle_event(Event::ButtonPressed(Button::Back));
+        assert_eq!(app.state().handled, vec!["inner"]);
+    }
+
+    fn record_ticks(state: &mut ListState, event: Event) -> bool {
+        if let Event::Tick(_) = event {
+            state.handled.push("tick");
+            return true;
+        }
+        false
+    }
+
+    #[test]
+    fn only_ticking_handlers_get_ticks() {
+        let mut bubbling = app(|_| Handler::new(record_ticks, list()));
+        bubbling.tick(core::time::Duration::from_millis(16));
+        assert!(bubbling.state().handled.is_empty());
+
+        let mut ticking = app(|_| Handler::ticking(record_ticks, list()));
+        ticking.tick(core::time::Duration::from_millis(16));
+        assert_eq!(ticking.state().handled, vec!["tick"]);
+    }
+
+    #[test]
+    fn closures_capture_their_context() {
+        let tag = "tapped";
+        let mut app = app(move |_| {
+            ItemSelector::new(
+                lens!(ListState, items),
+                lens!(ListState, selector),
+                move |text: &&'static str, _| {
+                    let text = *text;
+                    Handler::new(
+                        move |state: &mut ListState, event| match event {
+                            Event::PointerDown(_) => {
+                                state.handled.extend([text, tag]);
+                                true
+                            }
+                            _ => false,
+                        },
+                        Text::new(text.to_string()),
+                    )
+                },
+            )
+        });
+        app.handle_event(Event::PointerDown(Point::new(1, 15)));
+        assert_eq!(app.state().handled, vec!["b", "tapped"]);
     }
 }
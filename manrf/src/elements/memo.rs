@@ -0,0 +1,271 @@
+use core::any::TypeId;
+use core::hash::Hash;
+use std::cell::RefCell;
+
+use crate::cache::{cached, instance_key};
+use crate::defs::*;
+use crate::graphics::{GraphicOperation, GraphicOperationQueue};
+use crate::utils::*;
+
+type Select<S, K> = Rc<dyn Fn(&S) -> K>;
+
+/// Reuses the render tree and the paint operations of its child while the
+/// part of the state picked by `select` and the constraints are the same as
+/// in the previous render. Memos are told apart by their `select` function
+/// and `key`, so memos made by the same code, like the rows of a list, need
+/// different keys, like the index of their item.
+///
+/// The child must only depend on the selected part for its layout and
+/// painting. Its event handlers still get the whole state.
+pub struct Memo<S, K> {
+    // the type of the select function
+    kind: TypeId,
+    key: u64,
+    select: Select<S, K>,
+    child: Element<S>,
+}
+
+impl<S: State, K: PartialEq + Clone + 'static> Memo<S, K> {
+    pub fn new<F: Fn(&S) -> K + 'static>(key: impl Hash, select: F, child: Element<S>) -> Rc<Self> {
+        Rc::new(Self {
+            kind: TypeId::of::<F>(),
+            key: instance_key(key),
+            select: Rc::new(select),
+            child,
+        })
+    }
+}
+
+impl<S: State, K: PartialEq + Clone + 'static> ElementTrait<S> for Memo<S, K> {
+    fn render(&self, constraints: Constraints, state: &S) -> (Size, RenderNode<S>) {
+        let inputs = (self.key, (self.select)(state), constraints);
+        cached(self.kind, &inputs, || {
+            let (size, child_node) = self.child.render(constraints, state);
+            let painted: Element<S> = Rc::new(Painted {
+                child: self.child.clone(),
+                operations: RefCell::new(None),
+            });
+            (
+                size,
+                RenderNode::SingleChild {
+                    offset: Point::default(),
+                    size,
+                    renderer: painted,
+                    child: Box::new(child_node),
+                },
+            )
+        })
+    }
+}
+
+/// Child of a memo, recording what its subtree paints the first time to
+/// replay it afterwards.
+struct Painted<S> {
+    child: Element<S>,
+    // relative to the position of the child
+    operations: RefCell<Option<Vec<GraphicOperation>>>,
+}
+
+impl<S: State> ElementTrait<S> for Painted<S> {
+    fn render(&self, constraints: Constraints, state: &S) -> (Size, RenderNode<S>) {
+        self.child.render(constraints, state)
+    }
+
+    fn paint(&self, size: Size, pos: Point, display: &mut GraphicOperationQueue) {
+        self.child.paint(size, pos, display)
+    }
+
+    fn paint_with_child(
+        &self,
+        size: Size,
+        pos: Point,
+        child: &RenderNode<S>,
+        display: &mut GraphicOperationQueue,
+    ) {
+        let mut operations = self.operations.borrow_mut();
+        let operations = operations.get_or_insert_with(|| {
+            let mut queue = GraphicOperationQueue::new();
+            self.child
+                .paint_with_child(size, Point::zero(), child, &mut queue);
+            queue.drain().collect()
+        });
+        for operation in operations.iter() {
+            display.push(operation.clone().translated(pos));
+        }
+    }
+
+    fn focusable(&self) -> bool {
+        self.child.focusable()
+    }
+
+    fn wants_ticks(&self) -> bool {
+        self.child.wants_ticks()
+    }
+
+    fn event_handler(&self, state: &mut S, event: Event) -> EventResponse {
+        self.child.event_handler(state, event)
+    }
+
+    fn capture_event_handler(&self, state: &mut S, event: Event) -> EventResponse {
+        self.child.capture_event_handler(state, event)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::elements::Scope;
+    use crate::graphics::RecordingEndpoint;
+    use crate::{lens, App, Runner, Stack, Text};
+    use std::cell::Cell;
+
+    #[derive(Default)]
+    struct Dashboard {
+        title: &'static str,
+        clock: u32,
+    }
+
+    impl State for Dashboard {}
+
+    #[derive(Default)]
+    struct Counts {
+        renders: Cell<usize>,
+        paints: Cell<usize>,
+    }
+
+    /// Text counting how many times it is rendered and painted.
+    struct Counted {
+        text: Rc<Text>,
+        counts: Rc<Counts>,
+    }
+
+    impl ElementTrait<Dashboard> for Counted {
+        fn render(
+            &self,
+            constraints: Constraints,
+            state: &Dashboard,
+        ) -> (Size, RenderNode<Dashboard>) {
+            self.counts.renders.set(self.counts.renders.get() + 1);
+            self.text.render(constraints, state)
+        }
+
+        fn paint(&self, size: Size, pos: Point, display: &mut GraphicOperationQueue) {
+            self.counts.paints.set(self.counts.paints.get() + 1);
+            ElementTrait::<Dashboard>::paint(&*self.text, size, pos, display)
+        }
+    }
+
+    fn tick(state: &mut Dashboard, event: Event) -> bool {
+        match event {
+            Event::DirectionPressed(Direction::Up) => state.clock += 1,
+            Event::DirectionPressed(Direction::Down) => state.title = "Renamed",
+            _ => return false,
+        }
+        true
+    }
+
+    #[test]
+    fn unchanged_slices_reuse_their_render_and_paint() {
+        let counts = Rc::new(Counts::default());
+        let header_counts = counts.clone();
+        let mut app = App::new(
+            move |state: &Dashboard| {
+                let header = Rc::new(Counted {
+                    text: Text::new(state.title.to_string()),
+                    counts: header_counts.clone(),
+                });
+                crate::elements::Handler::new(
+                    tick,
+                    Stack::col(vec![
+                        Memo::new("header", |state: &Dashboard| state.title, header)
+                            as Element<Dashboard>,
+                        Text::new(format!("Clock {}", state.clock)),
+                    ]),
+                )
+            },
+            Size::new(100, 100),
+            RecordingEndpoint::new(),
+        );
+        app.draw();
+        app.handle_event(Event::DirectionPressed(Direction::Up));
+        app.draw();
+        assert_eq!(counts.renders.get(), 1);
+        assert_eq!(counts.paints.get(), 1);
+        assert!(app.endpoint.find_text("Clock 1").is_some());
+
+        app.handle_event(Event::DirectionPressed(Direction::Down));
+        app.draw();
+        assert_eq!(counts.renders.get(), 2);
+        assert_eq!(counts.paints.get(), 2);
+        assert!(app.endpoint.find_text("Renamed").is_some());
+    }
+
+    #[derive(Default)]
+    struct Home {
+        dashboard: Dashboard,
+        visits: u32,
+    }
+
+    impl State for Home {}
+
+    #[test]
+    fn memos_in_scopes_replay_their_paint() {
+        let counts = Rc::new(Counts::default());
+        let header_counts = counts.clone();
+        let mut app = App::new(
+            move |home: &Home| {
+                let header = Rc::new(Counted {
+                    text: Text::new(home.dashboard.title.to_string()),
+                    counts: header_counts.clone(),
+                });
+                crate::elements::Handler::new(
+                    |home: &mut Home, _| {
+                        home.visits += 1;
+                        true
+                    },
+                    Stack::col(vec![
+                        Scope::new(
+                            lens!(Home, dashboard),
+                            Memo::new("header", |state: &Dashboard| state.title, header),
+                        ) as Element<Home>,
+                        Text::new(format!("Visits {}", home.visits)),
+                    ]),
+                )
+            },
+            Size::new(100, 100),
+            RecordingEndpoint::new(),
+        );
+        app.draw();
+        app.handle_event(Event::ButtonPressed(Button::Principal));
+        app.draw();
+        assert_eq!(counts.renders.get(), 1);
+        assert_eq!(counts.paints.get(), 1);
+        assert!(app.endpoint.find_text("Visits 1").is_some());
+    }
+
+    #[test]
+    fn memos_with_the_same_key_keep_their_own_child() {
+        let mut app = App::new(
+            |state: &Dashboard| {
+                let memo: Element<Dashboard> = match state.clock {
+                    0 => Memo::new(
+                        0,
+                        |state: &Dashboard| state.title,
+                        Text::new("Left".to_string()),
+                    ),
+                    _ => Memo::new(
+                        0,
+                        |state: &Dashboard| state.title,
+                        Text::new("Right".to_string()),
+                    ),
+                };
+                crate::elements::Handler::new(tick, memo)
+            },
+            Size::new(100, 100),
+            RecordingEndpoint::new(),
+        );
+        app.handle_event(Event::DirectionPressed(Direction::Up));
+        app.draw();
+        assert!(app.endpoint.find_text("Right").is_some());
+    }
+}
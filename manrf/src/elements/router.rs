@@ -0,0 +1,286 @@
+use core::time::Duration;
+
+use crate::defs::*;
+use crate::lens::Lens;
+use crate::navigation::{Navigation, Navigator};
+use crate::utils::*;
+
+type RouteGenerator<S, R> = Rc<dyn Fn(&R, &S) -> Element<S>>;
+
+/// Shows the screen of the current route of a [`Navigator`], generated by
+/// `route`. Back pops the route when the screen ignores it.
+///
+/// The focus is kept for every route, so going back focuses the element
+/// that had the focus before the navigation.
+pub struct Router<S, R> {
+    navigator: Rc<dyn Lens<S, Navigator<R>>>,
+    route: RouteGenerator<S, R>,
+    slide: Option<Duration>,
+}
+
+impl<S: State, R: 'static> Router<S, R> {
+    pub fn new(
+        navigator: impl Lens<S, Navigator<R>>,
+        route: impl Fn(&R, &S) -> Element<S> + 'static,
+    ) -> Rc<Self> {
+        Rc::new(Self {
+            navigator: Rc::new(navigator),
+            route: Rc::new(route),
+            slide: None,
+        })
+    }
+
+    /// A router sliding the new screen in for `duration` after each
+    /// navigation, from the right when going forward and from the left when
+    /// going back. The app has to be ticked for it to move.
+    pub fn sliding(
+        navigator: impl Lens<S, Navigator<R>>,
+        duration: Duration,
+        route: impl Fn(&R, &S) -> Element<S> + 'static,
+    ) -> Rc<Self> {
+        Rc::new(Self {
+            navigator: Rc::new(navigator),
+            route: Rc::new(route),
+            slide: Some(duration),
+        })
+    }
+}
+
+impl<S: State, R: 'static> ElementTrait<S> for Router<S, R> {
+    fn render(&self, constraints: Constraints, state: &S) -> (Size, RenderNode<S>) {
+        let navigator = self.navigator.get(state);
+        let screen = (self.route)(navigator.current(), state);
+        let (size, screen_node) = screen.render(constraints, state);
+        let remaining = |progress: f32| (size.width as f32 * (1.0 - progress)) as i32;
+        let offset = match (self.slide, navigator.transition()) {
+            (Some(_), Some((Navigation::Pop, progress))) => Point::new(-remaining(progress), 0),
+            (Some(_), Some((_, progress))) => Point::new(remaining(progress), 0),
+            _ => Point::zero(),
+        };
+        let screen_node = RenderNode::SingleChild {
+            offset: Point::zero(),
+            size,
+            renderer: screen,
+            child: Box::new(screen_node),
+        };
+        (
+            size,
+            RenderNode::SingleChild {
+                offset,
+                size,
+                renderer: Rc::new(RouteScreen {
+                    id: navigator.current_id(),
+                }),
+                child: Box::new(screen_node),
+            },
+        )
+    }
+
+    fn wants_ticks(&self) -> bool {
+        self.slide.is_some()
+    }
+
+    fn event_handler(&self, state: &mut S, event: Event) -> EventResponse {
+        let navigator = self.navigator.get_mut(state);
+        match (event, self.slide) {
+            (Event::ButtonPressed(Button::Back), _) => navigator.pop().is_some().into(),
+            (Event::Tick(now), Some(duration)) => navigator.animate(now, duration).into(),
+            _ => EventResponse::Ignored,
+        }
+    }
+}
+
+/// Marks the screen of a route in the render tree, for its focus.
+struct RouteScreen {
+    id: u64,
+}
+
+impl<S: State> ElementTrait<S> for RouteScreen {
+    // only found in the nodes of a router, never rendered itself
+    fn render(&self, constraints: Constraints, _state: &S) -> (Size, RenderNode<S>) {
+        (constraints.min, RenderNode::Leaf)
+    }
+
+    fn focus_key(&self) -> Option<u64> {
+        Some(self.id)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::elements::{Handler, Scope};
+    use crate::graphics::{GraphicOperation, RecordingEndpoint};
+    use crate::{lens, App, ItemSelector, ItemSelectorState, Runner, Stack, Text};
+
+    #[derive(Clone, Copy, Debug, Default, PartialEq)]
+    enum Route {
+        #[default]
+        Home,
+        Details(u32),
+    }
+
+    #[derive(Default)]
+    struct Screens {
+        navigator: Navigator<Route>,
+        dialog: bool,
+        first: List,
+        second: List,
+        third: List,
+    }
+
+    #[derive(Default)]
+    struct List {
+        items: Vec<&'static str>,
+        selector: ItemSelectorState,
+    }
+
+    impl State for List {}
+
+    impl State for Screens {}
+
+    fn open_details(state: &mut Screens, event: Event) -> bool {
+        match event {
+            Event::ButtonPressed(Button::Principal | Button::Secondary) => {
+                let depth = state.navigator.routes().len() as u32;
+                state.navigator.push(Route::Details(depth));
+                true
+            }
+            // an open dialog is closed before leaving the screen
+            Event::ButtonPressed(Button::Back) if state.dialog => {
+                state.dialog = false;
+                true
+            }
+            _ => false,
+        }
+    }
+
+    fn screen(route: &Route, _: &Screens) -> Element<Screens> {
+        let title = match route {
+            Route::Home => "Home".to_string(),
+            Route::Details(depth) => format!("Details {}", depth),
+        };
+        Handler::new(open_details, Text::new(title))
+    }
+
+    fn text_x(endpoint: &RecordingEndpoint, text: &str) -> Option<i32> {
+        let index = endpoint.find_text(text)?;
+        match &endpoint.last_frame()[index] {
+            GraphicOperation::DrawText { position, .. } => Some(position.x),
+            _ => None,
+        }
+    }
+
+    #[test]
+    fn back_pops_until_the_root() {
+        let mut app = App::new(
+            |_: &Screens| Router::new(lens!(Screens, navigator), screen),
+            Size::new(100, 100),
+            RecordingEndpoint::new(),
+        );
+        app.handle_event(Event::ButtonPressed(Button::Principal));
+        app.handle_event(Event::ButtonPressed(Button::Principal));
+        app.draw();
+        assert!(app.endpoint.find_text("Details 2").is_some());
+
+        app.handle_event(Event::ButtonPressed(Button::Back));
+        app.draw();
+        assert!(app.endpoint.find_text("Details 1").is_some());
+
+        app.handle_event(Event::ButtonPressed(Button::Back));
+        app.handle_event(Event::ButtonPressed(Button::Back));
+        app.draw();
+        assert_eq!(app.state().navigator.routes(), &[Route::Home]);
+        assert!(app.endpoint.find_text("Home").is_some());
+    }
+
+    #[test]
+    fn screens_handle_back_before_the_router() {
+        let mut app = App::new(
+            |_: &Screens| Router::new(lens!(Screens, navigator), screen),
+            Size::new(100, 100),
+            RecordingEndpoint::new(),
+        );
+        app.handle_event(Event::ButtonPressed(Button::Principal));
+        app.state.dialog = true;
+
+        app.handle_event(Event::ButtonPressed(Button::Back));
+        assert!(!app.state().dialog);
+        assert_eq!(app.state().navigator.routes().len(), 2);
+
+        app.handle_event(Event::ButtonPressed(Button::Back));
+        assert_eq!(app.state().navigator.routes(), &[Route::Home]);
+    }
+
+    fn list() -> Element<List> {
+        ItemSelector::new(
+            lens!(List, items),
+            lens!(List, selector),
+            |item: &&'static str, _| Text::new(item.to_string()),
+        )
+    }
+
+    // a focusable list per line
+    fn lists(route: &Route, _: &Screens) -> Element<Screens> {
+        let mut lists: Vec<Element<Screens>> = vec![
+            Scope::new(lens!(Screens, first), list()),
+            Scope::new(lens!(Screens, second), list()),
+        ];
+        if let Route::Home = route {
+            lists.push(Scope::new(lens!(Screens, third), list()));
+        }
+        Handler::new(open_details, Stack::col(lists))
+    }
+
+    #[test]
+    fn every_route_keeps_its_focus() {
+        let mut app = App::new(
+            |_: &Screens| Router::new(lens!(Screens, navigator), lists),
+            Size::new(100, 100),
+            RecordingEndpoint::new(),
+        );
+        app.state.first.items = vec!["a"];
+        app.state.second.items = vec!["b"];
+        app.state.third.items = vec!["c"];
+        app.render();
+        app.handle_event(Event::DirectionPressed(Direction::Down));
+        app.handle_event(Event::DirectionPressed(Direction::Down));
+        let home_focus = app.focused_bounds().unwrap();
+        assert!(home_focus.top_left.y > 0);
+
+        app.handle_event(Event::ButtonPressed(Button::Secondary));
+        assert_eq!(app.focused_bounds().unwrap().top_left.y, 0);
+
+        app.handle_event(Event::ButtonPressed(Button::Back));
+        assert_eq!(app.focused_bounds(), Some(home_focus));
+    }
+
+    #[test]
+    fn new_screens_slide_in() {
+        let ms = Duration::from_millis;
+        let mut app = App::new(
+            |_: &Screens| {
+                Router::sliding(
+                    lens!(Screens, navigator),
+                    Duration::from_millis(200),
+                    screen,
+                )
+            },
+            Size::new(100, 100),
+            RecordingEndpoint::new(),
+        );
+        app.draw();
+        let home_x = text_x(&app.endpoint, "Home").unwrap();
+
+        app.handle_event(Event::ButtonPressed(Button::Principal));
+        app.tick(ms(1000));
+        app.tick(ms(1100));
+        app.draw();
+        let halfway = text_x(&app.endpoint, "Details 1").unwrap();
+        assert!(halfway > home_x);
+
+        app.tick(ms(1200));
+        app.draw();
+        assert_eq!(text_x(&app.endpoint, "Details 1"), Some(home_x));
+    }
+}
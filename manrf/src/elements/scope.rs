@@ -0,0 +1,182 @@
+use crate::defs::*;
+use crate::graphics::GraphicOperationQueue;
+use crate::lens::Lens;
+use crate::utils::*;
+
+/// Shows an element of a part `T` of the state, given by a lens, so nested
+/// screens only know about their own state.
+pub struct Scope<S, T> {
+    lens: Rc<dyn Lens<S, T>>,
+    child: Element<T>,
+}
+
+impl<S: State, T: State> Scope<S, T> {
+    pub fn new(lens: impl Lens<S, T>, child: Element<T>) -> Rc<Self> {
+        Rc::new(Self {
+            lens: Rc::new(lens),
+            child,
+        })
+    }
+}
+
+impl<S: State, T: State> ElementTrait<S> for Scope<S, T> {
+    fn render(&self, constraints: Constraints, state: &S) -> (Size, RenderNode<S>) {
+        let (size, node) = self.child.render(constraints, self.lens.get(state));
+        let child_node = scope_node(&self.lens, node.clone());
+        let scoped: Element<S> = Rc::new(Scoped {
+            lens: self.lens.clone(),
+            element: self.child.clone(),
+            node: Some(node),
+        });
+        (
+            size,
+            RenderNode::SingleChild {
+                offset: Point::default(),
+                size,
+                renderer: scoped,
+                child: Box::new(child_node),
+            },
+        )
+    }
+}
+
+/// Element of the part of the state, seen as an element of the whole state.
+struct Scoped<S, T> {
+    lens: Rc<dyn Lens<S, T>>,
+    element: Element<T>,
+    // the subtree of the element at the root of the scope, painted with the
+    // element so wrappers like memos see their own children
+    node: Option<RenderNode<T>>,
+}
+
+impl<S: State, T: State> ElementTrait<S> for Scoped<S, T> {
+    fn render(&self, constraints: Constraints, state: &S) -> (Size, RenderNode<S>) {
+        let (size, node) = self.element.render(constraints, self.lens.get(state));
+        (size, scope_node(&self.lens, node))
+    }
+
+    fn paint(&self, size: Size, pos: Point, display: &mut GraphicOperationQueue) {
+        self.element.paint(size, pos, display)
+    }
+
+    fn paint_with_child(
+        &self,
+        size: Size,
+        pos: Point,
+        child: &RenderNode<S>,
+        display: &mut GraphicOperationQueue,
+    ) {
+        match &self.node {
+            Some(node) => self.element.paint_with_child(size, pos, node, display),
+            None => {
+                self.paint(size, pos, display);
+                child.paint(display, pos);
+            }
+        }
+    }
+
+    fn focusable(&self) -> bool {
+        self.element.focusable()
+    }
+
+    fn wants_ticks(&self) -> bool {
+        self.element.wants_ticks()
+    }
+
+    fn event_handler(&self, state: &mut S, event: Event) -> EventResponse {
+        self.element.event_handler(self.lens.get_mut(state), event)
+    }
+
+    fn capture_event_handler(&self, state: &mut S, event: Event) -> EventResponse {
+        self.element
+            .capture_event_handler(self.lens.get_mut(state), event)
+    }
+}
+
+fn scope_node<S: State, T: State>(lens: &Rc<dyn Lens<S, T>>, node: RenderNode<T>) -> RenderNode<S> {
+    match node {
+        RenderNode::SingleChild {
+            offset,
+            size,
+            renderer,
+            child,
+        } => RenderNode::SingleChild {
+            offset,
+            size,
+            renderer: Rc::new(Scoped {
+                lens: lens.clone(),
+                element: renderer,
+                node: None,
+            }),
+            child: Box::new(scope_node(lens, *child)),
+        },
+        RenderNode::MultiChild {
+            offset,
+            size,
+            child,
+        } => RenderNode::MultiChild {
+            offset,
+            size,
+            child: child
+                .into_iter()
+                .map(|child| scope_node(lens, child))
+                .collect(),
+        },
+        RenderNode::Leaf => RenderNode::Leaf,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::graphics::RecordingEndpoint;
+    use crate::{lens, App, ItemSelector, ItemSelectorState, Runner, Stack, Text};
+
+    #[derive(Default)]
+    struct Settings {
+        options: Vec<&'static str>,
+        option: ItemSelectorState,
+    }
+
+    impl State for Settings {}
+
+    #[derive(Default)]
+    struct AppState {
+        settings: Settings,
+    }
+
+    impl State for AppState {}
+
+    // knows nothing about AppState
+    fn settings_screen(settings: &Settings) -> Element<Settings> {
+        Stack::col(vec![
+            Text::new(format!("{} options", settings.options.len())) as Element<Settings>,
+            ItemSelector::new(
+                lens!(Settings, options),
+                lens!(Settings, option),
+                |option: &&'static str, _| Text::new(option.to_string()),
+            ),
+        ])
+    }
+
+    #[test]
+    fn scoped_screens_change_their_part_of_the_state() {
+        let mut app = App::new(
+            |state: &AppState| {
+                Scope::new(lens!(AppState, settings), settings_screen(&state.settings))
+            },
+            Size::new(100, 100),
+            RecordingEndpoint::new(),
+        );
+        app.state.settings.options = vec!["dark", "light"];
+        app.render();
+
+        app.handle_event(Event::DirectionPressed(Direction::Down));
+        app.handle_event(Event::ButtonPressed(Button::Principal));
+        assert_eq!(app.state().settings.option.selected, Some(1));
+
+        app.draw();
+        assert!(app.endpoint.find_text("2 options").is_some());
+        assert!(app.endpoint.find_text("light").is_some());
+    }
+}
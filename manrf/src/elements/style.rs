@@ -1,5 +1,5 @@
 use crate::defs::*;
-use crate::graphics::{GraphicOperation, GraphicOperationQueue};
+use crate::graphics::{GraphicOperation, GraphicOperationQueue, LinearGradient};
 use crate::utils::*;
 
 pub struct BorderDefinition {
@@ -20,11 +20,71 @@ impl BorderDefinition {
     }
 }
 
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Background {
+    Color(Rgb888),
+    Gradient(LinearGradient),
+}
+
+/// Shadow painted behind the border box of a style.
+///
+/// There is no transparency, so the blur is approximated with `blur` stepped
+/// rectangles fading from `color` into `surface`, the color of whatever the
+/// shadow is cast on.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Shadow {
+    pub offset: Point,
+    pub color: Rgb888,
+    pub surface: Rgb888,
+    pub blur: u32,
+}
+
+impl Shadow {
+    pub const fn new(offset: Point, color: Rgb888, surface: Rgb888, blur: u32) -> Self {
+        Self {
+            offset,
+            color,
+            surface,
+            blur,
+        }
+    }
+
+    /// Shadow of a box lifted `level` pixels over the surface, cast downwards.
+    pub const fn elevation(level: u32, color: Rgb888, surface: Rgb888) -> Self {
+        Self::new(Point::new(0, level as i32), color, surface, level)
+    }
+
+    /// Space the shadow needs around the border box.
+    pub fn insets(&self) -> EdgeInsets {
+        let blur = self.blur as i32;
+        EdgeInsets::new(
+            (blur - self.offset.y).max(0) as u32,
+            (blur + self.offset.x).max(0) as u32,
+            (blur + self.offset.y).max(0) as u32,
+            (blur - self.offset.x).max(0) as u32,
+        )
+    }
+
+    fn step_color(&self, step: u32) -> Rgb888 {
+        // step 0 is the shadow itself, step `blur` the outermost rectangle
+        let total = self.blur + 1;
+        let mix = |shadow: u8, surface: u8| {
+            ((shadow as u32 * (total - step) + surface as u32 * step) / total) as u8
+        };
+        Rgb888::new(
+            mix(self.color.r(), self.surface.r()),
+            mix(self.color.g(), self.surface.g()),
+            mix(self.color.b(), self.surface.b()),
+        )
+    }
+}
+
 pub struct StyleDefinition {
-    pub background: Option<Rgb888>,
+    pub background: Option<Background>,
     pub margin: EdgeInsets,
     pub border: BorderDefinition,
     pub padding: EdgeInsets,
+    pub shadow: Option<Shadow>,
 }
 
 pub struct Style<S> {
@@ -42,10 +102,11 @@ impl<S> Style<S> {
     ) -> Rc<Self> {
         Rc::new(Self {
             style: StyleDefinition {
-                background,
+                background: background.map(Background::Color),
                 margin,
                 border,
                 padding,
+                shadow: None,
             },
             child,
         })
@@ -55,42 +116,72 @@ impl<S> Style<S> {
         Rc::new(Self { style, child })
     }
 
-    pub fn new_with_background(background: Rgb888, child: Element<S>) -> Rc<Self> {
+    pub fn new_with_background(background: Background, child: Element<S>) -> Rc<Self> {
         Rc::new(Self {
             style: StyleDefinition {
                 background: Some(background),
                 margin: EdgeInsets::all(0),
                 border: BorderDefinition::none(),
                 padding: EdgeInsets::all(0),
+                shadow: None,
             },
             child,
         })
     }
 
+    /// Space around the border box, the margin grown where the shadow
+    /// doesn't fit in it, so the shadow is part of the layout size.
+    fn outer_insets(&self) -> EdgeInsets {
+        match &self.style.shadow {
+            Some(shadow) => self.style.margin.max(&shadow.insets()),
+            None => self.style.margin,
+        }
+    }
+
     fn added_size(&self) -> Size {
-        self.style.margin.size() + self.style.border.size.size() + self.style.padding.size()
+        self.outer_insets().size() + self.style.border.size.size() + self.style.padding.size()
     }
 
     fn child_offset(&self) -> Point {
-        self.style.margin.top_left_offset()
+        self.outer_insets().top_left_offset()
             + self.style.border.size.top_left_offset()
             + self.style.padding.top_left_offset()
     }
 
     fn background_offset(&self) -> Point {
-        self.style.margin.top_left_offset() + self.style.border.size.top_left_offset()
+        self.outer_insets().top_left_offset() + self.style.border.size.top_left_offset()
     }
 
     fn background_size(&self, size: Size) -> Size {
-        size - self.style.margin.size() - self.style.border.size.size()
+        size - self.outer_insets().size() - self.style.border.size.size()
     }
 
     fn border_offset(&self) -> Point {
-        self.style.margin.top_left_offset()
+        self.outer_insets().top_left_offset()
     }
 
     fn border_size(&self, size: Size) -> Size {
-        size - self.style.margin.size()
+        size - self.outer_insets().size()
+    }
+
+    fn paint_shadow(
+        &self,
+        shadow: &Shadow,
+        size: Size,
+        pos: Point,
+        queue: &mut GraphicOperationQueue,
+    ) {
+        let top_left = self.border_offset() + pos + shadow.offset;
+        let size = self.border_size(size);
+        for step in (0..=shadow.blur).rev() {
+            queue.push(GraphicOperation::DrawRectangle {
+                rect: Rectangle::new(
+                    top_left - Point::new(step as i32, step as i32),
+                    size + Size::new(step * 2, step * 2),
+                ),
+                color: shadow.step_color(step),
+            });
+        }
     }
 }
 
@@ -113,11 +204,21 @@ impl<S: State> ElementTrait<S> for Style<S> {
 
     #[allow(unused_must_use)]
     fn paint(&self, size: Size, pos: Point, queue: &mut GraphicOperationQueue) {
-        if let Some(color) = self.style.background {
-            queue.push(GraphicOperation::DrawRectangle {
-                rect: Rectangle::new(self.background_offset() + pos, self.background_size(size)),
-                color: color,
-            });
+        if let Some(shadow) = &self.style.shadow {
+            self.paint_shadow(shadow, size, pos, queue);
+        }
+        let background_rect =
+            Rectangle::new(self.background_offset() + pos, self.background_size(size));
+        match self.style.background {
+            Some(Background::Color(color)) => queue.push(GraphicOperation::DrawRectangle {
+                rect: background_rect,
+                color,
+            }),
+            Some(Background::Gradient(gradient)) => queue.push(GraphicOperation::DrawGradient {
+                rect: background_rect,
+                gradient,
+            }),
+            None => {}
         }
         if !self.style.border.size.is_empty() {
             // paint left border
@@ -170,3 +271,47 @@ impl<S: State> ElementTrait<S> for Style<S> {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::graphics::{GraphicsEndpoint, RecordingEndpoint};
+
+    #[derive(Default)]
+    struct EmptyState;
+
+    impl State for EmptyState {}
+
+    #[test]
+    fn shadow_is_part_of_the_layout_size() {
+        let style = Style::<EmptyState>::new_with_style(
+            StyleDefinition {
+                background: Some(Background::Color(Rgb888::WHITE)),
+                margin: EdgeInsets::all(1),
+                border: BorderDefinition::none(),
+                padding: EdgeInsets::all(0),
+                shadow: Some(Shadow::new(
+                    Point::new(2, 2),
+                    Rgb888::BLACK,
+                    Rgb888::BLUE,
+                    1,
+                )),
+            },
+            crate::Box::exactly(Size::new(10, 10), Rgb888::RED, None),
+        );
+        let (size, _) = style.render(Constraints::up_to(Size::new(50, 50)), &EmptyState);
+        // left and top keep the margin, right and bottom grow to offset + blur
+        assert_eq!(size, Size::new(14, 14));
+
+        let mut queue = GraphicOperationQueue::new();
+        style.paint(size, Point::zero(), &mut queue);
+        let mut endpoint = RecordingEndpoint::new();
+        endpoint.draw_queue(queue);
+        assert_eq!(
+            endpoint.last_frame_text(),
+            "rect 2,2 12x12 #00007f\n\
+             rect 3,3 10x10 #000000\n\
+             rect 1,1 10x10 #ffffff"
+        );
+    }
+}
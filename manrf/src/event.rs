@@ -1,7 +1,25 @@
-#[cfg(test)]
+mod bindings;
+pub(crate) mod channel;
+mod input;
+mod response;
+#[cfg(any(test, feature = "simulator"))]
 pub mod simulator;
+mod source;
+mod timer;
 
-#[derive(Clone, Debug)]
+pub use bindings::*;
+pub use channel::{Disconnected, EventSender};
+pub use input::*;
+pub use response::*;
+pub use source::*;
+pub use timer::*;
+
+use core::time::Duration;
+use embedded_graphics::prelude::Point;
+
+use crate::pointer::Gesture;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
 pub enum Direction {
     Up,
     Down,
@@ -9,17 +27,118 @@ pub enum Direction {
     Right,
 }
 
-#[derive(Clone, Debug)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
 pub enum Button {
     Principal,
     Secondary,
     Back,
 }
 
+/// Phase of the dispatch in which a handler runs.
+///
+/// Events travel from the root to their target (capture), are delivered to
+/// the target, and travel back to the root (bubble). A handler handling
+/// the event stops the propagation.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Phase {
+    /// Before the target, from the root down.
+    Capture,
+    /// After the target ignored the event, from the target up.
+    #[default]
+    Bubble,
+}
+
+/// A physical key of the device, either a direction of the D-pad or a
+/// button.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Key {
+    Direction(Direction),
+    Button(Button),
+}
+
+impl Key {
+    pub fn pressed(self) -> Event {
+        match self {
+            Key::Direction(direction) => Event::DirectionPressed(direction),
+            Key::Button(button) => Event::ButtonPressed(button),
+        }
+    }
+
+    pub fn released(self) -> Event {
+        match self {
+            Key::Direction(direction) => Event::DirectionReleased(direction),
+            Key::Button(button) => Event::ButtonReleased(button),
+        }
+    }
+}
 
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, PartialEq, Eq)]
 pub enum Event {
+    /// Sent when the direction is pressed, and again at the repeat interval
+    /// while it is held when the input goes through an [`InputTracker`].
     DirectionPressed(Direction),
+    DirectionReleased(Direction),
     ButtonPressed(Button),
+    ButtonReleased(Button),
+    /// Sent once when a button is held longer than the long press threshold.
+    /// The `ButtonPressed` was already sent when the button went down.
+    ButtonLongPressed(Button),
+    /// A rotary encoder turned by the number of detents, positive when
+    /// clockwise. Lists and sliders move to the next value clockwise.
+    EncoderRotated(i32),
+    /// A touch or click started at the point.
+    PointerDown(Point),
+    /// A touch or the mouse moved to the point.
+    PointerMove(Point),
+    /// A touch or click ended at the point.
+    PointerUp(Point),
+    /// A gesture recognized by the app from the pointer events.
+    Gesture(Gesture),
+    /// A timer requested through `State::timers` is due. Sent to every
+    /// element.
+    Timer(TimerId),
+    /// Sent on each `App::tick`, with the current time, only to the elements
+    /// whose `wants_ticks` is true, like the ones made by
+    /// [`Handler::ticking`](crate::elements::Handler::ticking).
+    Tick(Duration),
 }
 
+impl Event {
+    /// The key pressed or released by this event.
+    pub fn key(&self) -> Option<Key> {
+        match self {
+            Event::DirectionPressed(direction) | Event::DirectionReleased(direction) => {
+                Some(Key::Direction(*direction))
+            }
+            Event::ButtonPressed(button)
+            | Event::ButtonReleased(button)
+            | Event::ButtonLongPressed(button) => Some(Key::Button(*button)),
+            _ => None,
+        }
+    }
+
+    /// Point of a pointer event.
+    ///
+    /// Elements receive it relative to their own top left corner.
+    pub fn position(&self) -> Option<Point> {
+        match self {
+            Event::PointerDown(point) | Event::PointerMove(point) | Event::PointerUp(point) => {
+                Some(*point)
+            }
+            Event::Gesture(gesture) => Some(gesture.position()),
+            _ => None,
+        }
+    }
+
+    /// The same event with its position moved into a coordinate system
+    /// whose origin is `origin`.
+    pub(crate) fn relative_to(&self, origin: Point) -> Event {
+        match self {
+            Event::PointerDown(point) => Event::PointerDown(*point - origin),
+            Event::PointerMove(point) => Event::PointerMove(*point - origin),
+            Event::PointerUp(point) => Event::PointerUp(*point - origin),
+            Event::Gesture(gesture) => Event::Gesture(gesture.relative_to(origin)),
+            other => other.clone(),
+        }
+    }
+}
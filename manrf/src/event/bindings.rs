@@ -0,0 +1,183 @@
+use core::fmt;
+use core::str::FromStr;
+
+use super::{Button, Direction, Event, Key};
+
+impl FromStr for Key {
+    type Err = ();
+
+    /// Parses the names used in key binding files, like `up` or `back`.
+    fn from_str(name: &str) -> Result<Self, Self::Err> {
+        match name.to_ascii_lowercase().as_str() {
+            "up" => Ok(Key::Direction(Direction::Up)),
+            "down" => Ok(Key::Direction(Direction::Down)),
+            "left" => Ok(Key::Direction(Direction::Left)),
+            "right" => Ok(Key::Direction(Direction::Right)),
+            "principal" => Ok(Key::Button(Button::Principal)),
+            "secondary" => Ok(Key::Button(Button::Secondary)),
+            "back" => Ok(Key::Button(Button::Back)),
+            _ => Err(()),
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum BindingError {
+    /// The line isn't a comment nor `input = key`.
+    Syntax { line: usize },
+    /// The input isn't known to the device.
+    UnknownInput { line: usize, name: String },
+    /// The key isn't one of the keys of [`Key`].
+    UnknownKey { line: usize, name: String },
+}
+
+impl fmt::Display for BindingError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            BindingError::Syntax { line } => write!(f, "line {}: expected `input = key`", line),
+            BindingError::UnknownInput { line, name } => {
+                write!(f, "line {}: unknown input `{}`", line, name)
+            }
+            BindingError::UnknownKey { line, name } => {
+                write!(f, "line {}: unknown key `{}`", line, name)
+            }
+        }
+    }
+}
+
+impl std::error::Error for BindingError {}
+
+/// Maps the raw inputs of a device, like keyboard keys, gamepad buttons or
+/// pins, to the [`Key`]s of the app.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct KeyBindings<I> {
+    bindings: Vec<(I, Key)>,
+}
+
+impl<I> Default for KeyBindings<I> {
+    fn default() -> Self {
+        Self {
+            bindings: Vec::new(),
+        }
+    }
+}
+
+impl<I: PartialEq> KeyBindings<I> {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Binds the input to the key, replacing its previous binding. Several
+    /// inputs can be bound to the same key.
+    pub fn bind(&mut self, input: I, key: Key) -> &mut Self {
+        self.unbind(&input);
+        self.bindings.push((input, key));
+        self
+    }
+
+    pub fn unbind(&mut self, input: &I) {
+        self.bindings.retain(|(bound, _)| bound != input);
+    }
+
+    pub fn key(&self, input: &I) -> Option<Key> {
+        self.bindings
+            .iter()
+            .find(|(bound, _)| bound == input)
+            .map(|(_, key)| *key)
+    }
+
+    pub fn pressed(&self, input: &I) -> Option<Event> {
+        self.key(input).map(Key::pressed)
+    }
+
+    pub fn released(&self, input: &I) -> Option<Event> {
+        self.key(input).map(Key::released)
+    }
+
+    /// Reads bindings from `input = key` lines, where `#` starts a comment.
+    /// Inputs are named by `parse_input`, and keys are the lowercase names of
+    /// the directions and buttons.
+    ///
+    /// ```text
+    /// # WASD layout
+    /// W = up
+    /// S = down
+    /// Return = principal
+    /// ```
+    pub fn parse(
+        config: &str,
+        parse_input: impl Fn(&str) -> Option<I>,
+    ) -> Result<Self, BindingError> {
+        let mut bindings = Self::new();
+        for (index, line) in config.lines().enumerate() {
+            let line_number = index + 1;
+            let line = line.split('#').next().unwrap_or_default().trim();
+            if line.is_empty() {
+                continue;
+            }
+            let (input, key) = line
+                .split_once('=')
+                .ok_or(BindingError::Syntax { line: line_number })?;
+            let (input, key) = (input.trim(), key.trim());
+            let key = key.parse().map_err(|_| BindingError::UnknownKey {
+                line: line_number,
+                name: key.to_string(),
+            })?;
+            let input = parse_input(input).ok_or_else(|| BindingError::UnknownInput {
+                line: line_number,
+                name: input.to_string(),
+            })?;
+            bindings.bind(input, key);
+        }
+        Ok(bindings)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_pin_bindings() {
+        let config = "
+            # buttons wired to GPIO pins
+            0 = principal
+            35 = Back  # boot button
+
+            12 = down
+        ";
+        let bindings = KeyBindings::<u8>::parse(config, |pin| pin.parse().ok()).unwrap();
+        assert_eq!(
+            bindings.pressed(&35),
+            Some(Event::ButtonPressed(Button::Back))
+        );
+        assert_eq!(
+            bindings.released(&12),
+            Some(Event::DirectionReleased(Direction::Down))
+        );
+        assert_eq!(bindings.key(&1), None);
+    }
+
+    #[test]
+    fn reports_the_faulty_line() {
+        let parse = |config| KeyBindings::<u8>::parse(config, |pin| pin.parse().ok());
+        assert_eq!(
+            parse("0 = up\n1 down"),
+            Err(BindingError::Syntax { line: 2 })
+        );
+        assert_eq!(
+            parse("0 = jump"),
+            Err(BindingError::UnknownKey {
+                line: 1,
+                name: "jump".to_string()
+            })
+        );
+        assert_eq!(
+            parse("gpio0 = up"),
+            Err(BindingError::UnknownInput {
+                line: 1,
+                name: "gpio0".to_string()
+            })
+        );
+    }
+}
@@ -0,0 +1,143 @@
+use core::fmt;
+use std::sync::mpsc::{channel, Receiver, Sender};
+
+use super::Event;
+
+pub(crate) enum Posted<S> {
+    Event(Event),
+    Update(Box<dyn FnOnce(&mut S) + Send>),
+}
+
+/// The app receiving the posts was dropped.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Disconnected;
+
+impl fmt::Display for Disconnected {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "the app was dropped")
+    }
+}
+
+impl std::error::Error for Disconnected {}
+
+/// Handle to post events and state updates to an app from other threads,
+/// obtained with `App::sender`.
+///
+/// Posts are handled in order by the next `App::tick` on the UI thread.
+pub struct EventSender<S> {
+    sender: Sender<Posted<S>>,
+}
+
+impl<S> Clone for EventSender<S> {
+    fn clone(&self) -> Self {
+        Self {
+            sender: self.sender.clone(),
+        }
+    }
+}
+
+impl<S> EventSender<S> {
+    /// Dispatches the event to the elements of the app.
+    pub fn post_event(&self, event: Event) -> Result<(), Disconnected> {
+        self.post(Posted::Event(event))
+    }
+
+    /// Runs the update on the state, then renders the app again.
+    pub fn post_update(
+        &self,
+        update: impl FnOnce(&mut S) + Send + 'static,
+    ) -> Result<(), Disconnected> {
+        self.post(Posted::Update(Box::new(update)))
+    }
+
+    fn post(&self, posted: Posted<S>) -> Result<(), Disconnected> {
+        self.sender.send(posted).map_err(|_| Disconnected)
+    }
+}
+
+/// Receiving end of the posts, owned by the app.
+pub(crate) struct Inbox<S> {
+    sender: Sender<Posted<S>>,
+    receiver: Receiver<Posted<S>>,
+}
+
+impl<S> Default for Inbox<S> {
+    fn default() -> Self {
+        let (sender, receiver) = channel();
+        Self { sender, receiver }
+    }
+}
+
+impl<S> Inbox<S> {
+    pub fn sender(&self) -> EventSender<S> {
+        EventSender {
+            sender: self.sender.clone(),
+        }
+    }
+
+    pub fn try_recv(&self) -> Option<Posted<S>> {
+        self.receiver.try_recv().ok()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::defs::{Element, Runner, State};
+    use crate::elements::Handler;
+    use crate::graphics::RecordingEndpoint;
+    use crate::{App, Text};
+    use core::time::Duration;
+    use embedded_graphics::prelude::Size;
+
+    #[derive(Default)]
+    struct Sensor {
+        temperature: i32,
+        alarms: usize,
+    }
+
+    impl State for Sensor {}
+
+    fn alarm(state: &mut Sensor, event: Event) -> bool {
+        if let Event::ButtonLongPressed(_) = event {
+            state.alarms += 1;
+            return true;
+        }
+        false
+    }
+
+    fn sensor(state: &Sensor) -> Element<Sensor> {
+        Handler::new(alarm, Text::new(format!("{}C", state.temperature)))
+    }
+
+    #[test]
+    fn posts_from_other_threads_are_handled_on_tick() {
+        let mut app = App::new(sensor, Size::new(100, 100), RecordingEndpoint::new());
+        let sender = app.sender();
+        std::thread::spawn(move || {
+            sender.post_update(|state| state.temperature = 21).unwrap();
+            sender
+                .post_event(Event::ButtonLongPressed(crate::event::Button::Back))
+                .unwrap();
+        })
+        .join()
+        .unwrap();
+
+        assert_eq!(app.state().temperature, 0);
+        app.tick(Duration::from_millis(16));
+        app.draw();
+        assert_eq!(app.state().alarms, 1);
+        assert!(app.endpoint.find_text("21C").is_some());
+    }
+
+    #[test]
+    fn posting_to_a_dropped_app_fails() {
+        let app = App::new(sensor, Size::new(100, 100), RecordingEndpoint::new());
+        let sender = app.sender();
+        drop(app);
+        assert_eq!(
+            sender.post_update(|state| state.alarms = 1),
+            Err(Disconnected)
+        );
+    }
+}
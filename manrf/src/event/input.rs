@@ -0,0 +1,192 @@
+use core::time::Duration;
+
+use super::{Event, Key};
+
+/// Thresholds used by [`InputTracker`] to turn raw presses and releases into
+/// long presses and repeats.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct InputTiming {
+    /// How long a button must be held to send `ButtonLongPressed`.
+    pub long_press: Duration,
+    /// How long a direction must be held before it starts repeating.
+    pub repeat_delay: Duration,
+    /// Time between two repeats of a held direction.
+    pub repeat_interval: Duration,
+}
+
+impl Default for InputTiming {
+    fn default() -> Self {
+        Self {
+            long_press: Duration::from_millis(600),
+            repeat_delay: Duration::from_millis(400),
+            repeat_interval: Duration::from_millis(100),
+        }
+    }
+}
+
+struct HeldKey {
+    key: Key,
+    // when the next repeat or long press is due, `None` once a long press was sent
+    due: Option<Duration>,
+}
+
+/// Keeps track of the held keys to generate long presses and auto-repeat.
+///
+/// Timestamps are durations since any fixed instant, like the boot of the
+/// device, and must not go backwards.
+#[derive(Default)]
+pub struct InputTracker {
+    timing: InputTiming,
+    held: Vec<HeldKey>,
+}
+
+impl InputTracker {
+    /// Panics if the repeat interval of `timing` is zero.
+    pub fn new(timing: InputTiming) -> Self {
+        assert_repeats(&timing);
+        Self {
+            timing,
+            held: Vec::new(),
+        }
+    }
+
+    pub fn timing(&self) -> InputTiming {
+        self.timing
+    }
+
+    /// Panics if the repeat interval of `timing` is zero.
+    pub fn set_timing(&mut self, timing: InputTiming) {
+        assert_repeats(&timing);
+        self.timing = timing;
+    }
+
+    pub fn is_held(&self, key: Key) -> bool {
+        self.held.iter().any(|held| held.key == key)
+    }
+
+    /// Records a raw event, returning it when it has to be dispatched.
+    ///
+    /// Presses of a key that is already held are dropped, as repeats are
+    /// generated by [`InputTracker::poll`] instead.
+    pub fn process(&mut self, event: Event, now: Duration) -> Option<Event> {
+        let key = match event.key() {
+            Some(key) => key,
+            None => return Some(event),
+        };
+        match event {
+            Event::DirectionPressed(_) | Event::ButtonPressed(_) => {
+                if self.is_held(key) {
+                    return None;
+                }
+                let wait = match key {
+                    Key::Direction(_) => self.timing.repeat_delay,
+                    Key::Button(_) => self.timing.long_press,
+                };
+                self.held.push(HeldKey {
+                    key,
+                    due: Some(now + wait),
+                });
+            }
+            Event::DirectionReleased(_) | Event::ButtonReleased(_) => {
+                self.held.retain(|held| held.key != key);
+            }
+            _ => {}
+        }
+        Some(event)
+    }
+
+    /// Events due at `now` for the held keys: a `DirectionPressed` when a
+    /// repeat is due, and a single `ButtonLongPressed`.
+    ///
+    /// Repeats missed between two late polls are dropped, and the next one
+    /// is a repeat interval after `now`.
+    pub fn poll(&mut self, now: Duration) -> Vec<Event> {
+        let mut events = Vec::new();
+        for held in self.held.iter_mut() {
+            match held.key {
+                Key::Direction(direction) => {
+                    if held.due.is_some_and(|due| due <= now) {
+                        events.push(Event::DirectionPressed(direction));
+                        held.due = Some(now + self.timing.repeat_interval);
+                    }
+                }
+                Key::Button(button) => {
+                    if held.due.is_some_and(|due| due <= now) {
+                        events.push(Event::ButtonLongPressed(button));
+                        held.due = None;
+                    }
+                }
+            }
+        }
+        events
+    }
+}
+
+fn assert_repeats(timing: &InputTiming) {
+    assert!(
+        !timing.repeat_interval.is_zero(),
+        "the repeat interval must not be zero"
+    );
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::event::{Button, Direction};
+
+    fn ms(millis: u64) -> Duration {
+        Duration::from_millis(millis)
+    }
+
+    #[test]
+    fn held_direction_repeats_until_released() {
+        let mut tracker = InputTracker::default();
+        let down = Event::DirectionPressed(Direction::Down);
+        assert_eq!(tracker.process(down.clone(), ms(0)), Some(down.clone()));
+        // the key repeat of the host is ignored
+        assert_eq!(tracker.process(down.clone(), ms(50)), None);
+        assert!(tracker.poll(ms(399)).is_empty());
+        assert_eq!(tracker.poll(ms(400)), vec![down.clone()]);
+        // a late poll doesn't catch up with the missed repeats
+        assert_eq!(tracker.poll(ms(650)), vec![down.clone()]);
+        assert!(tracker.poll(ms(749)).is_empty());
+        assert_eq!(tracker.poll(ms(750)), vec![down.clone()]);
+
+        let up = Event::DirectionReleased(Direction::Down);
+        assert_eq!(tracker.process(up.clone(), ms(660)), Some(up));
+        assert!(tracker.poll(ms(2000)).is_empty());
+    }
+
+    #[test]
+    fn long_press_is_sent_once() {
+        let mut tracker = InputTracker::new(InputTiming {
+            long_press: ms(1000),
+            ..InputTiming::default()
+        });
+        tracker.process(Event::ButtonPressed(Button::Back), ms(0));
+        assert!(tracker.poll(ms(999)).is_empty());
+        assert_eq!(
+            tracker.poll(ms(1000)),
+            vec![Event::ButtonLongPressed(Button::Back)]
+        );
+        assert!(tracker.poll(ms(5000)).is_empty());
+        assert!(tracker.is_held(Key::Button(Button::Back)));
+    }
+
+    #[test]
+    #[should_panic(expected = "repeat interval")]
+    fn repeats_need_an_interval() {
+        InputTracker::default().set_timing(InputTiming {
+            repeat_interval: Duration::ZERO,
+            ..InputTiming::default()
+        });
+    }
+
+    #[test]
+    fn short_press_is_not_long() {
+        let mut tracker = InputTracker::default();
+        tracker.process(Event::ButtonPressed(Button::Principal), ms(0));
+        tracker.process(Event::ButtonReleased(Button::Principal), ms(100));
+        assert!(tracker.poll(ms(1000)).is_empty());
+    }
+}
@@ -0,0 +1,130 @@
+/// What a handler did with an event.
+///
+/// Handled events stop their propagation, and the app renders again only
+/// when one of them changed the state. Handlers returning `bool` are
+/// assumed to change the state when they handle the event.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, PartialOrd, Ord)]
+pub enum EventResponse {
+    /// Not handled, the event keeps propagating.
+    #[default]
+    Ignored,
+    /// Handled without changing the state, like moving past the last item
+    /// of a list that doesn't let the focus go.
+    Consumed,
+    /// Handled, and the state changed.
+    Changed,
+}
+
+impl EventResponse {
+    pub fn is_handled(self) -> bool {
+        self != EventResponse::Ignored
+    }
+
+    pub fn is_changed(self) -> bool {
+        self == EventResponse::Changed
+    }
+
+    /// `Changed` when `changed`, `Consumed` otherwise.
+    pub fn handled(changed: bool) -> Self {
+        match changed {
+            true => EventResponse::Changed,
+            false => EventResponse::Consumed,
+        }
+    }
+}
+
+impl From<bool> for EventResponse {
+    fn from(handled: bool) -> Self {
+        match handled {
+            true => EventResponse::Changed,
+            false => EventResponse::Ignored,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::defs::{Element, Runner, State};
+    use crate::event::{Button, Event};
+    use crate::graphics::RecordingEndpoint;
+    use crate::{lens, App, ItemSelector, ItemSelectorState, Text};
+    use embedded_graphics::prelude::Size;
+    use std::cell::Cell;
+    use std::rc::Rc;
+
+    struct List {
+        items: Vec<&'static str>,
+        selector: ItemSelectorState,
+    }
+
+    impl Default for List {
+        fn default() -> Self {
+            Self {
+                items: vec!["a", "b"],
+                selector: ItemSelectorState::default(),
+            }
+        }
+    }
+
+    impl State for List {}
+
+    fn list() -> Element<List> {
+        ItemSelector::new(
+            lens!(List, items),
+            lens!(List, selector),
+            |item: &&'static str, _| Text::new(item.to_string()),
+        )
+    }
+
+    #[test]
+    fn handled_events_without_changes_dont_render() {
+        let renders = Rc::new(Cell::new(0));
+        let counter = renders.clone();
+        let mut app = App::new(
+            move |_: &List| {
+                counter.set(counter.get() + 1);
+                list()
+            },
+            Size::new(100, 100),
+            RecordingEndpoint::new(),
+        );
+        app.add_shortcut(
+            crate::event::Phase::Bubble,
+            |_: &mut List, event| match event {
+                Event::ButtonPressed(Button::Back) => EventResponse::Consumed,
+                _ => EventResponse::Ignored,
+            },
+        );
+        assert_eq!(renders.get(), 1);
+
+        app.handle_event(Event::ButtonPressed(Button::Principal));
+        assert_eq!(app.state().selector.selected, Some(0));
+        assert_eq!(renders.get(), 2);
+
+        app.handle_event(Event::ButtonPressed(Button::Principal));
+        app.handle_event(Event::ButtonPressed(Button::Back));
+        assert_eq!(renders.get(), 2);
+    }
+
+    #[test]
+    fn empty_lists_ignore_selection() {
+        let mut app = App::new(
+            |_: &List| list(),
+            Size::new(100, 100),
+            RecordingEndpoint::new(),
+        );
+        app.state.items.clear();
+        app.render();
+        app.handle_event(Event::ButtonPressed(Button::Principal));
+        assert_eq!(app.state().selector.selected, None);
+    }
+
+    #[test]
+    fn handlers_returning_bool_change_the_state() {
+        assert_eq!(EventResponse::from(true), EventResponse::Changed);
+        assert_eq!(EventResponse::from(false), EventResponse::Ignored);
+        assert!(EventResponse::Consumed.is_handled());
+        assert!(!EventResponse::Consumed.is_changed());
+    }
+}
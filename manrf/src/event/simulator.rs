@@ -1,31 +1,254 @@
+use core::time::Duration;
+use std::collections::VecDeque;
+use std::sync::OnceLock;
+
+use embedded_graphics::pixelcolor::{PixelColor, Rgb888};
 use embedded_graphics_simulator::{
-    sdl2::{Keycode, MouseButton},
-    SimulatorEvent,
+    sdl2::{Keycode, MouseButton, MouseWheelDirection},
+    SimulatorDisplay, SimulatorEvent, Window,
 };
 
-use super::{Event, Button, Direction};
+use super::{BindingError, Button, Direction, Event, InputSource, Key, KeyBindings};
+
+/// A key or mouse button of the simulator window.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum SimulatorInput {
+    Key(Keycode),
+    Mouse(MouseButton),
+}
+
+impl SimulatorInput {
+    /// Parses SDL key names, like `W` or `Left Shift`, and `MouseLeft`,
+    /// `MouseMiddle`, `MouseRight`, `MouseX1` and `MouseX2`.
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "MouseLeft" => Some(SimulatorInput::Mouse(MouseButton::Left)),
+            "MouseMiddle" => Some(SimulatorInput::Mouse(MouseButton::Middle)),
+            "MouseRight" => Some(SimulatorInput::Mouse(MouseButton::Right)),
+            "MouseX1" => Some(SimulatorInput::Mouse(MouseButton::X1)),
+            "MouseX2" => Some(SimulatorInput::Mouse(MouseButton::X2)),
+            name => Keycode::from_name(name).map(SimulatorInput::Key),
+        }
+    }
+}
+
+impl KeyBindings<SimulatorInput> {
+    /// Arrows for the directions, Z, X and Space for the principal, back and
+    /// secondary buttons, and the right mouse button for back.
+    pub fn simulator() -> Self {
+        let mut bindings = Self::new();
+        bindings
+            .bind(
+                SimulatorInput::Key(Keycode::Up),
+                Key::Direction(Direction::Up),
+            )
+            .bind(
+                SimulatorInput::Key(Keycode::Down),
+                Key::Direction(Direction::Down),
+            )
+            .bind(
+                SimulatorInput::Key(Keycode::Left),
+                Key::Direction(Direction::Left),
+            )
+            .bind(
+                SimulatorInput::Key(Keycode::Right),
+                Key::Direction(Direction::Right),
+            )
+            .bind(
+                SimulatorInput::Key(Keycode::Z),
+                Key::Button(Button::Principal),
+            )
+            .bind(SimulatorInput::Key(Keycode::X), Key::Button(Button::Back))
+            .bind(
+                SimulatorInput::Key(Keycode::Space),
+                Key::Button(Button::Secondary),
+            )
+            .bind(
+                SimulatorInput::Mouse(MouseButton::Right),
+                Key::Button(Button::Back),
+            );
+        bindings
+    }
+
+    /// Reads simulator bindings, naming inputs as [`SimulatorInput::from_name`].
+    pub fn parse_simulator(config: &str) -> Result<Self, BindingError> {
+        Self::parse(config, SimulatorInput::from_name)
+    }
+
+    /// Maps a window event. The left mouse button is the pointer, unless
+    /// it is bound to a key, and the mouse wheel is an encoder.
+    pub fn simulator_event(&self, event: SimulatorEvent) -> Option<Event> {
+        match event {
+            SimulatorEvent::KeyDown { keycode, .. } => self.pressed(&SimulatorInput::Key(keycode)),
+            SimulatorEvent::KeyUp { keycode, .. } => self.released(&SimulatorInput::Key(keycode)),
+            SimulatorEvent::MouseButtonDown { mouse_btn, point } => {
+                match self.pressed(&SimulatorInput::Mouse(mouse_btn)) {
+                    None if mouse_btn == MouseButton::Left => Some(Event::PointerDown(point)),
+                    event => event,
+                }
+            }
+            SimulatorEvent::MouseButtonUp { mouse_btn, point } => {
+                match self.released(&SimulatorInput::Mouse(mouse_btn)) {
+                    None if mouse_btn == MouseButton::Left => Some(Event::PointerUp(point)),
+                    event => event,
+                }
+            }
+            SimulatorEvent::MouseMove { point } => Some(Event::PointerMove(point)),
+            // scrolling down turns clockwise
+            SimulatorEvent::MouseWheel {
+                scroll_delta,
+                direction,
+            } => match (scroll_delta.y, direction) {
+                (0, _) => None,
+                (steps, MouseWheelDirection::Flipped) => Some(Event::EncoderRotated(steps)),
+                (steps, _) => Some(Event::EncoderRotated(-steps)),
+            },
+            _ => None,
+        }
+    }
+}
+
+/// The simulator window, as the input source of an app.
+pub struct SimulatorWindow {
+    window: Window,
+    bindings: KeyBindings<SimulatorInput>,
+    pending: VecDeque<Event>,
+    pointer_down: bool,
+    closed: bool,
+}
+
+impl SimulatorWindow {
+    pub fn new(window: Window, bindings: KeyBindings<SimulatorInput>) -> Self {
+        Self {
+            window,
+            bindings,
+            pending: VecDeque::new(),
+            pointer_down: false,
+            closed: false,
+        }
+    }
+
+    /// Shows the display. The window must be updated once before polling it.
+    pub fn update<C>(&mut self, display: &SimulatorDisplay<C>)
+    where
+        C: PixelColor + Into<Rgb888> + From<Rgb888>,
+    {
+        self.window.update(display);
+    }
 
+    /// Whether the window was asked to close.
+    pub fn is_closed(&self) -> bool {
+        self.closed
+    }
+}
+
+impl InputSource for SimulatorWindow {
+    fn poll_event(&mut self, _now: Duration) -> Option<Event> {
+        if self.pending.is_empty() {
+            for event in self.window.events() {
+                match event {
+                    SimulatorEvent::Quit => self.closed = true,
+                    other => {
+                        let event = self.bindings.simulator_event(other);
+                        self.pending
+                            .extend(event.filter(|event| drags(&mut self.pointer_down, event)));
+                    }
+                }
+            }
+        }
+        self.pending.pop_front()
+    }
+}
+
+/// Whether the event is forwarded, keeping track of the pointer: the mouse
+/// only moves the pointer while it is down, so moves without a button held
+/// are dropped.
+fn drags(pointer_down: &mut bool, event: &Event) -> bool {
+    match event {
+        Event::PointerDown(_) => *pointer_down = true,
+        Event::PointerUp(_) => *pointer_down = false,
+        Event::PointerMove(_) => return *pointer_down,
+        _ => {}
+    }
+    true
+}
+
+/// Maps the window events with the default bindings.
 impl TryFrom<SimulatorEvent> for Event {
     type Error = ();
 
     fn try_from(event: SimulatorEvent) -> Result<Self, Self::Error> {
-        match event {
-            SimulatorEvent::MouseButtonDown { mouse_btn, .. } => match mouse_btn {
-                MouseButton::Left => Ok(Event::ButtonPressed(Button::Principal)),
-                MouseButton::Right => Ok(Event::ButtonPressed(Button::Back)),
-                _ => Err(()),
-            },
-            SimulatorEvent::KeyDown { keycode, .. } => match keycode {
-                Keycode::Up => Ok(Event::DirectionPressed(Direction::Up)),
-                Keycode::Down => Ok(Event::DirectionPressed(Direction::Down)),
-                Keycode::Left => Ok(Event::DirectionPressed(Direction::Left)),
-                Keycode::Right => Ok(Event::DirectionPressed(Direction::Right)),
-                Keycode::Z => Ok(Event::ButtonPressed(Button::Principal)),
-                Keycode::X => Ok(Event::ButtonPressed(Button::Back)),
-                Keycode::Space => Ok(Event::ButtonPressed(Button::Secondary)),
-                _ => Err(()),
-            },
-            _ => Err(()),
+        static BINDINGS: OnceLock<KeyBindings<SimulatorInput>> = OnceLock::new();
+        BINDINGS
+            .get_or_init(KeyBindings::simulator)
+            .simulator_event(event)
+            .ok_or(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use embedded_graphics::prelude::Point;
+    use embedded_graphics_simulator::sdl2::Mod;
+
+    fn key_down(keycode: Keycode) -> SimulatorEvent {
+        SimulatorEvent::KeyDown {
+            keycode,
+            keymod: Mod::NOMOD,
+            repeat: false,
         }
     }
+
+    #[test]
+    fn default_bindings_keep_the_arrows_and_zx() {
+        assert_eq!(
+            Event::try_from(key_down(Keycode::Z)),
+            Ok(Event::ButtonPressed(Button::Principal))
+        );
+        assert_eq!(Event::try_from(key_down(Keycode::W)), Err(()));
+        assert_eq!(
+            Event::try_from(SimulatorEvent::MouseButtonDown {
+                mouse_btn: MouseButton::Left,
+                point: Point::new(3, 4),
+            }),
+            Ok(Event::PointerDown(Point::new(3, 4)))
+        );
+    }
+
+    #[test]
+    fn moves_are_only_forwarded_while_the_pointer_is_down() {
+        let mut pointer_down = false;
+        let point = Point::new(3, 4);
+        assert!(!drags(&mut pointer_down, &Event::PointerMove(point)));
+        assert!(drags(&mut pointer_down, &Event::PointerDown(point)));
+        assert!(drags(&mut pointer_down, &Event::PointerMove(point)));
+        assert!(drags(&mut pointer_down, &Event::PointerUp(point)));
+        assert!(!drags(&mut pointer_down, &Event::PointerMove(point)));
+    }
+
+    #[test]
+    fn rebound_keys() {
+        let mut bindings = KeyBindings::simulator();
+        bindings
+            .bind(
+                SimulatorInput::Key(Keycode::W),
+                Key::Direction(Direction::Up),
+            )
+            .bind(
+                SimulatorInput::Mouse(MouseButton::Left),
+                Key::Button(Button::Principal),
+            );
+        assert_eq!(
+            bindings.simulator_event(key_down(Keycode::W)),
+            Some(Event::DirectionPressed(Direction::Up))
+        );
+        assert_eq!(
+            bindings.simulator_event(SimulatorEvent::MouseButtonUp {
+                mouse_btn: MouseButton::Left,
+                point: Point::new(3, 4),
+            }),
+            Some(Event::ButtonReleased(Button::Principal))
+        );
+    }
 }
@@ -0,0 +1,227 @@
+use core::time::Duration;
+
+use embedded_hal::digital::v2::InputPin;
+
+use super::{Event, Key};
+
+/// Something producing raw input events, like buttons, an encoder, a touch
+/// panel or the simulator window, to drive an `App` with
+/// `App::poll_source`.
+pub trait InputSource {
+    /// The next event available at `now`, or `None` when there are no more
+    /// until a later time.
+    fn poll_event(&mut self, now: Duration) -> Option<Event>;
+}
+
+impl<T: InputSource + ?Sized> InputSource for Box<T> {
+    fn poll_event(&mut self, now: Duration) -> Option<Event> {
+        (**self).poll_event(now)
+    }
+}
+
+/// Polls the sources in order, until one of them has an event.
+impl<T: InputSource> InputSource for Vec<T> {
+    fn poll_event(&mut self, now: Duration) -> Option<Event> {
+        self.iter_mut().find_map(|source| source.poll_event(now))
+    }
+}
+
+/// A button wired to an input pin, pressing its key once the level of the
+/// pin has been stable for the debounce time.
+///
+/// Pins failing to read are considered unchanged.
+pub struct DebouncedButton<P> {
+    pin: P,
+    key: Key,
+    active_low: bool,
+    debounce: Duration,
+    pressed: bool,
+    // the level read that differs from `pressed`, and since when
+    changing_since: Option<Duration>,
+}
+
+impl<P: InputPin> DebouncedButton<P> {
+    /// A button pulling the pin low when pressed, debounced for 20ms.
+    pub fn new(pin: P, key: Key) -> Self {
+        Self {
+            pin,
+            key,
+            active_low: true,
+            debounce: Duration::from_millis(20),
+            pressed: false,
+            changing_since: None,
+        }
+    }
+
+    /// The button pulls the pin high when pressed.
+    pub fn active_high(mut self) -> Self {
+        self.active_low = false;
+        self
+    }
+
+    pub fn with_debounce(mut self, debounce: Duration) -> Self {
+        self.debounce = debounce;
+        self
+    }
+
+    pub fn is_pressed(&self) -> bool {
+        self.pressed
+    }
+
+    pub fn into_inner(self) -> P {
+        self.pin
+    }
+
+    fn read(&self) -> Option<bool> {
+        match self.active_low {
+            true => self.pin.is_low().ok(),
+            false => self.pin.is_high().ok(),
+        }
+    }
+}
+
+impl<P: InputPin> InputSource for DebouncedButton<P> {
+    fn poll_event(&mut self, now: Duration) -> Option<Event> {
+        let pressed = self.read()?;
+        if pressed == self.pressed {
+            self.changing_since = None;
+            return None;
+        }
+        let since = *self.changing_since.get_or_insert(now);
+        if now < since + self.debounce {
+            return None;
+        }
+        self.pressed = pressed;
+        self.changing_since = None;
+        Some(match pressed {
+            true => self.key.pressed(),
+            false => self.key.released(),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::defs::{Element, State};
+    use crate::elements::Handler;
+    use crate::event::Button;
+    use crate::graphics::RecordingEndpoint;
+    use crate::{App, Text};
+    use embedded_graphics::prelude::Size;
+    use std::cell::Cell;
+    use std::rc::Rc;
+
+    #[derive(Clone, Default)]
+    struct MockPin {
+        high: Rc<Cell<bool>>,
+    }
+
+    impl InputPin for MockPin {
+        type Error = ();
+
+        fn is_high(&self) -> Result<bool, Self::Error> {
+            Ok(self.high.get())
+        }
+
+        fn is_low(&self) -> Result<bool, Self::Error> {
+            Ok(!self.high.get())
+        }
+    }
+
+    fn ms(millis: u64) -> Duration {
+        Duration::from_millis(millis)
+    }
+
+    #[test]
+    fn bounces_are_ignored() {
+        let pin = MockPin::default();
+        pin.high.set(true);
+        let mut button = DebouncedButton::new(pin.clone(), Key::Button(Button::Back));
+        assert_eq!(button.poll_event(ms(0)), None);
+
+        pin.high.set(false);
+        assert_eq!(button.poll_event(ms(10)), None);
+        pin.high.set(true);
+        assert_eq!(button.poll_event(ms(15)), None);
+        pin.high.set(false);
+        assert_eq!(button.poll_event(ms(20)), None);
+        assert_eq!(button.poll_event(ms(39)), None);
+        assert_eq!(
+            button.poll_event(ms(40)),
+            Some(Event::ButtonPressed(Button::Back))
+        );
+        assert!(button.is_pressed());
+
+        pin.high.set(true);
+        assert_eq!(button.poll_event(ms(100)), None);
+        assert_eq!(
+            button.poll_event(ms(120)),
+            Some(Event::ButtonReleased(Button::Back))
+        );
+    }
+
+    #[test]
+    fn sources_are_polled_in_order() {
+        let first = MockPin::default();
+        let second = MockPin::default();
+        let mut sources = vec![
+            DebouncedButton::new(first.clone(), Key::Button(Button::Principal))
+                .active_high()
+                .with_debounce(Duration::ZERO),
+            DebouncedButton::new(second.clone(), Key::Button(Button::Back))
+                .active_high()
+                .with_debounce(Duration::ZERO),
+        ];
+        first.high.set(true);
+        second.high.set(true);
+        assert_eq!(
+            sources.poll_event(ms(0)),
+            Some(Event::ButtonPressed(Button::Principal))
+        );
+        assert_eq!(
+            sources.poll_event(ms(0)),
+            Some(Event::ButtonPressed(Button::Back))
+        );
+        assert_eq!(sources.poll_event(ms(0)), None);
+    }
+
+    #[derive(Default)]
+    struct Presses {
+        count: usize,
+    }
+
+    impl State for Presses {}
+
+    fn count(state: &mut Presses, event: Event) -> bool {
+        if let Event::ButtonPressed(Button::Principal) = event {
+            state.count += 1;
+            return true;
+        }
+        false
+    }
+
+    fn presses(_: &Presses) -> Element<Presses> {
+        Handler::new(count, Text::new("Press".to_string()))
+    }
+
+    #[test]
+    fn app_is_driven_by_a_pin() {
+        let pin = MockPin::default();
+        pin.high.set(true);
+        let mut button = DebouncedButton::new(pin.clone(), Key::Button(Button::Principal));
+        let mut app = App::new(presses, Size::new(100, 100), RecordingEndpoint::new());
+
+        for (millis, high) in [
+            (0, false),
+            (30, false),
+            (60, true),
+            (90, true),
+            (120, false),
+        ] {
+            pin.high.set(high);
+            app.poll_source(&mut button, ms(millis));
+        }
+        assert_eq!(app.state().count, 1);
+    }
+}
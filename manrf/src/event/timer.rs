@@ -0,0 +1,192 @@
+use core::time::Duration;
+
+/// Identifies a timer requested to [`Timers`], and the `Event::Timer` it fires.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct TimerId(u32);
+
+struct Timer {
+    id: TimerId,
+    due: Duration,
+    interval: Option<Duration>,
+}
+
+/// Timers requested by the state, fired by `App::tick`.
+///
+/// States owning timers keep them in a field and return it from
+/// `State::timers`, so event handlers can schedule and cancel them.
+#[derive(Default)]
+pub struct Timers {
+    now: Duration,
+    next_id: u32,
+    timers: Vec<Timer>,
+}
+
+impl Timers {
+    /// Time of the last tick.
+    pub fn now(&self) -> Duration {
+        self.now
+    }
+
+    /// Fires a timer once, after `delay`.
+    pub fn once(&mut self, delay: Duration) -> TimerId {
+        self.add(delay, None)
+    }
+
+    /// Fires a timer every `interval`, until it is cancelled.
+    ///
+    /// Panics if the interval is zero.
+    pub fn every(&mut self, interval: Duration) -> TimerId {
+        assert!(!interval.is_zero(), "timer intervals can't be zero");
+        self.add(interval, Some(interval))
+    }
+
+    /// Stops a timer, returning whether it was still pending.
+    pub fn cancel(&mut self, id: TimerId) -> bool {
+        let count = self.timers.len();
+        self.timers.retain(|timer| timer.id != id);
+        self.timers.len() != count
+    }
+
+    pub fn is_pending(&self, id: TimerId) -> bool {
+        self.timers.iter().any(|timer| timer.id == id)
+    }
+
+    /// When the next timer is due, to know how long the device can sleep.
+    pub fn next_due(&self) -> Option<Duration> {
+        self.timers.iter().map(|timer| timer.due).min()
+    }
+
+    /// Moves the time to `now`, returning the timers due by then in the order
+    /// they were due.
+    ///
+    /// An interval timer fires only once per call, even if several of its
+    /// intervals elapsed, and keeps its rhythm afterwards.
+    pub fn advance(&mut self, now: Duration) -> Vec<TimerId> {
+        self.now = now;
+        let mut fired: Vec<(Duration, TimerId)> = Vec::new();
+        self.timers.retain_mut(|timer| {
+            if timer.due > now {
+                return true;
+            }
+            fired.push((timer.due, timer.id));
+            match timer.interval {
+                Some(interval) => {
+                    while timer.due <= now {
+                        timer.due += interval;
+                    }
+                    true
+                }
+                None => false,
+            }
+        });
+        fired.sort_by_key(|(due, _)| *due);
+        fired.into_iter().map(|(_, id)| id).collect()
+    }
+
+    fn add(&mut self, delay: Duration, interval: Option<Duration>) -> TimerId {
+        let id = TimerId(self.next_id);
+        self.next_id += 1;
+        self.timers.push(Timer {
+            id,
+            due: self.now + delay,
+            interval,
+        });
+        id
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::defs::{Element, Runner, State};
+    use crate::elements::Handler;
+    use crate::event::{Button, Event};
+    use crate::graphics::RecordingEndpoint;
+    use crate::{App, Stack, Text};
+    use embedded_graphics::prelude::Size;
+
+    fn ms(millis: u64) -> Duration {
+        Duration::from_millis(millis)
+    }
+
+    #[test]
+    fn timers_fire_in_due_order() {
+        let mut timers = Timers::default();
+        let blink = timers.every(ms(300));
+        let toast = timers.once(ms(500));
+        assert_eq!(timers.next_due(), Some(ms(300)));
+
+        assert_eq!(timers.advance(ms(299)), vec![]);
+        assert_eq!(timers.advance(ms(500)), vec![blink, toast]);
+        assert!(!timers.is_pending(toast));
+        // missed intervals are not caught up
+        assert_eq!(timers.advance(ms(1300)), vec![blink]);
+        assert_eq!(timers.next_due(), Some(ms(1500)));
+
+        assert!(timers.cancel(blink));
+        assert_eq!(timers.advance(ms(5000)), vec![]);
+    }
+
+    #[test]
+    fn delays_start_at_the_last_tick() {
+        let mut timers = Timers::default();
+        timers.advance(ms(1000));
+        let id = timers.once(ms(100));
+        assert_eq!(timers.advance(ms(1099)), vec![]);
+        assert_eq!(timers.advance(ms(1100)), vec![id]);
+    }
+
+    #[derive(Default)]
+    struct ToastState {
+        timers: Timers,
+        toast: Option<TimerId>,
+        seconds: u64,
+    }
+
+    impl State for ToastState {
+        fn timers(&mut self) -> Option<&mut Timers> {
+            Some(&mut self.timers)
+        }
+    }
+
+    fn toast_handler(state: &mut ToastState, event: Event) -> bool {
+        match event {
+            Event::ButtonPressed(Button::Principal) => {
+                state.toast = Some(state.timers.once(Duration::from_secs(2)));
+                true
+            }
+            Event::Timer(id) if state.toast == Some(id) => {
+                state.toast = None;
+                true
+            }
+            Event::Tick(now) if now.as_secs() != state.seconds => {
+                state.seconds = now.as_secs();
+                true
+            }
+            _ => false,
+        }
+    }
+
+    fn toast_view(state: &ToastState) -> Element<ToastState> {
+        let mut items: Vec<Element<ToastState>> = vec![Text::new(format!("{}s", state.seconds))];
+        if state.toast.is_some() {
+            items.push(Text::new("Saved".to_string()));
+        }
+        Handler::ticking(toast_handler, Stack::col(items))
+    }
+
+    #[test]
+    fn timers_fire_events_and_rerender() {
+        let mut app = App::new(toast_view, Size::new(100, 100), RecordingEndpoint::new());
+        app.handle_event(Event::ButtonPressed(Button::Principal));
+        app.tick(ms(1500));
+        app.draw();
+        assert!(app.endpoint.find_text("Saved").is_some());
+        assert!(app.endpoint.find_text("1s").is_some());
+
+        app.tick(ms(2000));
+        app.draw();
+        assert!(app.endpoint.find_text("Saved").is_none());
+        assert!(app.endpoint.find_text("2s").is_some());
+    }
+}
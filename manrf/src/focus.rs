@@ -0,0 +1,205 @@
+use crate::defs::{RenderNode, State};
+use crate::utils::*;
+
+/// How focus moves when the focused element ignores a direction.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum FocusTraversal {
+    /// To the nearest focusable element in the pressed direction.
+    #[default]
+    Spatial,
+    /// To the next (down, right) or previous (up, left) focusable element in
+    /// render order.
+    Ordered,
+}
+
+/// A focusable element found in the render tree.
+pub(crate) struct Focusable {
+    /// Index of the child taken at every node from the root, always 0 for
+    /// a `SingleChild`.
+    pub path: Vec<usize>,
+    /// Absolute bounds of the element.
+    pub bounds: Rectangle,
+}
+
+/// Focusable elements of the tree, in render (pre-order) order.
+pub(crate) fn focusables<S: State>(root: &RenderNode<S>) -> Vec<Focusable> {
+    let mut found = Vec::new();
+    collect(root, Point::zero(), &mut Vec::new(), &mut found);
+    found
+}
+
+fn collect<S: State>(
+    node: &RenderNode<S>,
+    origin: Point,
+    path: &mut Vec<usize>,
+    found: &mut Vec<Focusable>,
+) {
+    match node {
+        RenderNode::SingleChild {
+            offset,
+            size,
+            renderer,
+            child,
+        } => {
+            let origin = origin + *offset;
+            if renderer.focusable() {
+                found.push(Focusable {
+                    path: path.clone(),
+                    bounds: Rectangle::new(origin, *size),
+                });
+            }
+            path.push(0);
+            collect(child, origin, path, found);
+            path.pop();
+        }
+        RenderNode::MultiChild { offset, child, .. } => {
+            let origin = origin + *offset;
+            for (index, item) in child.iter().enumerate() {
+                path.push(index);
+                collect(item, origin, path, found);
+                path.pop();
+            }
+        }
+        RenderNode::Leaf => {}
+    }
+}
+
+/// Focus keys of the elements of the tree, in render order.
+pub(crate) fn focus_keys<S: State>(node: &RenderNode<S>, keys: &mut Vec<u64>) {
+    match node {
+        RenderNode::SingleChild {
+            renderer, child, ..
+        } => {
+            keys.extend(renderer.focus_key());
+            focus_keys(child, keys);
+        }
+        RenderNode::MultiChild { child, .. } => {
+            for item in child {
+                focus_keys(item, keys);
+            }
+        }
+        RenderNode::Leaf => {}
+    }
+}
+
+/// Index of the element receiving focus when moving from `current`.
+pub(crate) fn next_focus(
+    focusables: &[Focusable],
+    current: usize,
+    direction: &Direction,
+    traversal: FocusTraversal,
+) -> Option<usize> {
+    match traversal {
+        FocusTraversal::Ordered => match direction {
+            Direction::Down | Direction::Right => {
+                Some(current + 1).filter(|next| *next < focusables.len())
+            }
+            Direction::Up | Direction::Left => current.checked_sub(1),
+        },
+        FocusTraversal::Spatial => {
+            let from = focusables[current].bounds.center();
+            focusables
+                .iter()
+                .enumerate()
+                .filter(|(index, _)| *index != current)
+                .filter_map(|(index, focusable)| {
+                    let delta = focusable.bounds.center() - from;
+                    let (along, across) = match direction {
+                        Direction::Up => (-delta.y, delta.x),
+                        Direction::Down => (delta.y, delta.x),
+                        Direction::Left => (-delta.x, delta.y),
+                        Direction::Right => (delta.x, delta.y),
+                    };
+                    // elements out of line are further away than aligned ones
+                    (along > 0).then(|| (along + 2 * across.abs(), index))
+                })
+                .min()
+                .map(|(_, index)| index)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::defs::{Element, Runner};
+    use crate::graphics::RecordingEndpoint;
+    use crate::{lens, App, ItemSelector, ItemSelectorState, Stack, Text};
+
+    #[derive(Default)]
+    struct TwoLists {
+        items: Vec<&'static str>,
+        first: ItemSelectorState,
+        second: ItemSelectorState,
+    }
+
+    impl State for TwoLists {}
+
+    fn item(text: &&'static str, _active: bool) -> Element<TwoLists> {
+        Text::new(text.to_string())
+    }
+
+    fn two_lists(_: &TwoLists) -> Element<TwoLists> {
+        Stack::col(vec![
+            ItemSelector::new(lens!(TwoLists, items), lens!(TwoLists, first), item)
+                as Element<TwoLists>,
+            ItemSelector::new(lens!(TwoLists, items), lens!(TwoLists, second), item),
+        ])
+    }
+
+    fn app() -> App<TwoLists, RecordingEndpoint> {
+        let mut app = App::new(two_lists, Size::new(100, 100), RecordingEndpoint::new());
+        app.state.items = vec!["a", "b"];
+        app.render();
+        app
+    }
+
+    #[test]
+    fn focus_moves_when_the_focused_element_ignores_a_direction() {
+        let mut app = app();
+        assert_eq!(app.focused_bounds().unwrap().top_left, Point::new(0, 0));
+
+        app.handle_event(Event::DirectionPressed(Direction::Down));
+        assert_eq!(app.state().first.active, 1);
+
+        // the first list is at its last item, focus goes to the second one
+        app.handle_event(Event::DirectionPressed(Direction::Down));
+        assert_eq!(app.focused_bounds().unwrap().top_left, Point::new(0, 20));
+
+        app.handle_event(Event::DirectionPressed(Direction::Down));
+        assert_eq!(app.state().first.active, 1);
+        assert_eq!(app.state().second.active, 1);
+
+        app.handle_event(Event::DirectionPressed(Direction::Up));
+        app.handle_event(Event::DirectionPressed(Direction::Up));
+        assert_eq!(app.focused_bounds().unwrap().top_left, Point::new(0, 0));
+        assert_eq!(app.state().second.active, 0);
+    }
+
+    #[test]
+    fn ordered_traversal_follows_render_order() {
+        let mut app = app();
+        app.set_focus_traversal(FocusTraversal::Ordered);
+        app.handle_event(Event::DirectionPressed(Direction::Right));
+        assert_eq!(app.focused_bounds().unwrap().top_left, Point::new(0, 20));
+        app.handle_event(Event::DirectionPressed(Direction::Right));
+        assert_eq!(app.focused_bounds().unwrap().top_left, Point::new(0, 20));
+    }
+
+    #[test]
+    fn encoder_steps_through_items_then_focusables() {
+        let mut app = app();
+        app.handle_event(Event::EncoderRotated(3));
+        assert_eq!(app.state().first.active, 1);
+
+        // the first list can't go further clockwise
+        app.handle_event(Event::EncoderRotated(1));
+        assert_eq!(app.focused_bounds().unwrap().top_left, Point::new(0, 20));
+        app.handle_event(Event::EncoderRotated(1));
+        assert_eq!(app.state().second.active, 1);
+
+        app.handle_event(Event::EncoderRotated(-1));
+        app.handle_event(Event::EncoderRotated(-1));
+        assert_eq!(app.focused_bounds().unwrap().top_left, Point::new(0, 0));
+    }
+}
@@ -1,20 +1,30 @@
 use crate::defs::State;
 use crate::elements;
-use crate::elements::{BorderDefinition, StyleDefinition};
+use crate::elements::{Background, BorderDefinition, StyleDefinition};
 use crate::palette::PALETTE_DREAM;
+use crate::screenshot::{assert_screenshot, offscreen_app};
 use crate::utils::*;
 
-use crate::{ItemSelector, ItemSelectorState};
-use embedded_graphics::prelude::Size;
+use crate::defs::Runner;
+use crate::graphics::{
+    EmbeddedGraphicsEndpoint, GraphicsEndpoint, RecordingEndpoint, Rotation, Transform,
+    TransformedTarget,
+};
+use crate::{lens, App, ItemSelector, ItemSelectorState};
+use embedded_graphics::pixelcolor::BinaryColor;
+use embedded_graphics::prelude::{Dimensions, OriginDimensions, PointsIter, Size};
+use embedded_graphics_simulator::SimulatorDisplay;
 
 const BORDERED_STYLE: StyleDefinition = StyleDefinition {
-    background: Some(PALETTE_DREAM.darkest),
+    background: Some(Background::Color(PALETTE_DREAM.darkest)),
     margin: EdgeInsets::symmetric(2, 4),
     border: BorderDefinition::new(PALETTE_DREAM.dark, EdgeInsets::new(1, 2, 3, 4)),
     padding: EdgeInsets::all(2),
+    shadow: None,
 };
 
-use crate::{testing_helpers::test_in_window, Element, Stack, Text};
+use crate::testing_helpers::{run_in_window, window_endpoint};
+use crate::{Element, Stack, Text};
 
 #[derive(Clone)]
 struct Key {
@@ -69,18 +79,26 @@ static GO_BACK: elements::EventHandler<AppState> = |state, event| {
     }
 };
 
+static GO_HOME: elements::EventHandler<AppState> = |state, event| {
+    if let Event::ButtonLongPressed(Button::Back) = event {
+        state.keys_selected_state = ItemSelectorState::default();
+        true
+    } else {
+        false
+    }
+};
+
 fn item_selector_view(_: &AppState) -> Element<AppState> {
     ItemSelector::<AppState, Key>::new(
-        |state| &state.keys,
-        |state| state.keys_selected_state.clone(),
-        |state, new_state| state.keys_selected_state = new_state,
+        lens!(AppState, keys),
+        lens!(AppState, keys_selected_state),
         |key: &Key, selected: bool| {
             elements::Style::new_with_style(
                 StyleDefinition {
                     background: if selected {
-                        Some(PALETTE_DREAM.light)
+                        Some(Background::Color(PALETTE_DREAM.light))
                     } else {
-                        Some(PALETTE_DREAM.darkest)
+                        Some(Background::Color(PALETTE_DREAM.darkest))
                     },
                     ..BORDERED_STYLE
                 },
@@ -97,18 +115,15 @@ fn selected_view(state: &AppState) -> Element<AppState> {
         .map(|index| &state.keys[index])
         .unwrap_or(&Key::default())
         .clone();
-    elements::Handler::new(
-        GO_BACK,
-        elements::border(
-            BorderDefinition {
-                color: PALETTE_DREAM.darkest,
-                size: EdgeInsets::all(2),
-            },
-            Text::new(format!(
-                "Selected: {} {}",
-                selected_key.text, selected_key.key
-            )),
-        ),
+    elements::border(
+        BorderDefinition {
+            color: PALETTE_DREAM.darkest,
+            size: EdgeInsets::all(2),
+        },
+        Text::new(format!(
+            "Selected: {} {}",
+            selected_key.text, selected_key.key
+        )),
     )
 }
 
@@ -139,8 +154,162 @@ fn main_menu(state: &AppState) -> Element<AppState> {
     )
 }
 
+fn keys_app<U: GraphicsEndpoint>(endpoint: U) -> App<AppState, U> {
+    let mut app = App::new(main_menu, Size::new(135, 240), endpoint);
+    app.add_shortcut(Phase::Bubble, GO_BACK);
+    app.add_shortcut(Phase::Bubble, GO_HOME);
+    app
+}
+
 #[ignore]
 #[test]
 fn create_keys_app() {
-    test_in_window(Size::new(135, 240), main_menu, |_, _| ());
+    run_in_window(keys_app(window_endpoint(Size::new(135, 240))), |_, _| ());
+}
+
+#[test]
+fn draws_on_binary_color_display() {
+    let size = Size::new(135, 240);
+    let display: SimulatorDisplay<BinaryColor> = SimulatorDisplay::new(size);
+    let mut app = App::new(main_menu, size, EmbeddedGraphicsEndpoint::new(display));
+    app.draw();
+
+    let target = &app.endpoint.target;
+    // dark background maps to Off, while the text is drawn in white
+    assert_eq!(target.get_pixel(Point::new(0, 0)), BinaryColor::Off);
+    assert!(target
+        .bounding_box()
+        .points()
+        .any(|point| target.get_pixel(point) == BinaryColor::On));
+}
+
+fn screenshot_path(name: &str) -> String {
+    format!("{}/screenshots/{}.png", env!("CARGO_MANIFEST_DIR"), name)
+}
+
+#[test]
+fn main_menu_screenshots() {
+    let mut app = offscreen_app(Size::new(135, 240), main_menu);
+    app.set_optimize(true);
+    app.draw();
+    assert_screenshot(&app.endpoint.target, screenshot_path("main_menu"));
+    // the screen background is only painted where nothing covers it
+    let stats = app.optimization_stats();
+    assert!(stats.pixels_saved() >= 3 * 82 * 16);
+
+    app.handle_event(Event::DirectionPressed(Direction::Down));
+    app.draw();
+    assert_screenshot(
+        &app.endpoint.target,
+        screenshot_path("main_menu_second_active"),
+    );
+
+    app.handle_event(Event::ButtonPressed(Button::Principal));
+    app.draw();
+    assert_screenshot(
+        &app.endpoint.target,
+        screenshot_path("main_menu_second_selected"),
+    );
+}
+
+#[test]
+fn active_key_is_drawn_over_light_background() {
+    let mut app = App::new(main_menu, Size::new(135, 240), RecordingEndpoint::new());
+    app.draw();
+    assert_eq!(
+        app.endpoint.color_behind_text("Key: First 1"),
+        Some(PALETTE_DREAM.light)
+    );
+    assert_eq!(
+        app.endpoint.color_behind_text("Key: Second 2"),
+        Some(PALETTE_DREAM.darkest)
+    );
+
+    app.handle_event(Event::DirectionPressed(Direction::Down));
+    app.draw();
+    assert_eq!(
+        app.endpoint.color_behind_text("Key: Second 2"),
+        Some(PALETTE_DREAM.light)
+    );
+    assert!(app
+        .endpoint
+        .last_frame_text()
+        .contains("rect 27,118 82x16 #32d9ce\nrect 23,117 4x20 #f41c7d"));
+}
+
+#[test]
+fn frames_are_only_drawn_after_changes() {
+    let mut app = keys_app(RecordingEndpoint::new());
+    app.draw();
+    app.draw();
+    assert_eq!(app.endpoint.frames().len(), 1);
+
+    // nothing handles it, so nothing changes
+    app.handle_event(Event::DirectionPressed(Direction::Right));
+    app.draw();
+    assert_eq!(app.endpoint.frames().len(), 1);
+
+    app.handle_event(Event::DirectionPressed(Direction::Down));
+    app.draw();
+    app.draw();
+    assert_eq!(app.endpoint.frames().len(), 2);
+}
+
+#[test]
+fn held_keys_repeat_and_long_press() {
+    let ms = core::time::Duration::from_millis;
+    let mut app = keys_app(RecordingEndpoint::new());
+
+    app.handle_input(Event::DirectionPressed(Direction::Down), ms(0));
+    assert_eq!(app.state().keys_selected_state.active, 1);
+    app.poll_input(ms(450));
+    assert_eq!(app.state().keys_selected_state.active, 2);
+    app.handle_input(Event::DirectionReleased(Direction::Down), ms(460));
+    app.poll_input(ms(1000));
+    assert_eq!(app.state().keys_selected_state.active, 2);
+
+    app.handle_input(Event::ButtonPressed(Button::Principal), ms(1100));
+    app.handle_input(Event::ButtonReleased(Button::Principal), ms(1200));
+    assert_eq!(app.state().keys_selected_state.selected, Some(2));
+
+    app.handle_input(Event::ButtonPressed(Button::Back), ms(1300));
+    assert_eq!(app.state().keys_selected_state.selected, None);
+    assert_eq!(app.state().keys_selected_state.active, 2);
+    app.poll_input(ms(2000));
+    assert_eq!(app.state().keys_selected_state.active, 0);
+}
+
+#[test]
+fn capture_shortcuts_run_before_the_focused_element() {
+    let mut app = keys_app(RecordingEndpoint::new());
+    app.add_shortcut(Phase::Capture, |_, event| {
+        event == Event::ButtonPressed(Button::Principal)
+    });
+    app.handle_event(Event::ButtonPressed(Button::Principal));
+    assert_eq!(app.state().keys_selected_state.selected, None);
+    app.handle_event(Event::DirectionPressed(Direction::Down));
+    assert_eq!(app.state().keys_selected_state.active, 1);
+}
+
+#[test]
+fn rotated_display_lays_out_against_logical_size() {
+    let reference_size = Size::new(135, 240);
+    let mut reference = offscreen_app(reference_size, main_menu);
+    reference.draw();
+
+    let transform = Transform::rotate(Rotation::Deg90).mirrored();
+    let panel = SimulatorDisplay::<Rgb888>::new(Size::new(240, 135));
+    let mut app = App::fullscreen(
+        main_menu,
+        EmbeddedGraphicsEndpoint::new(TransformedTarget::new(panel, transform)),
+    );
+    app.draw();
+
+    let panel = app.endpoint.target.inner();
+    for point in Rectangle::new(Point::zero(), reference_size).points() {
+        assert_eq!(
+            panel.get_pixel(transform.point(point, panel.size())),
+            reference.endpoint.target.get_pixel(point)
+        );
+    }
 }
@@ -1,11 +1,27 @@
 use embedded_graphics::{
     mono_font::{ascii::FONT_6X10, MonoTextStyle},
     pixelcolor::Rgb888,
-    prelude::{DrawTarget, Point},
+    prelude::{DrawTarget, Point, PointsIter, Size},
     primitives::Rectangle,
     text::Text,
-    Drawable,
+    Drawable, Pixel,
 };
+
+mod gradient;
+mod optimize;
+mod recording;
+mod transform;
+
+pub use gradient::*;
+pub use optimize::*;
+pub use recording::*;
+pub use transform::*;
+
+/// Drawing instruction produced by elements while painting.
+///
+/// Colors are always expressed as `Rgb888`, endpoints are responsible of
+/// mapping them to the color type of the display they draw to.
+#[derive(Clone, Debug, PartialEq, Eq)]
 pub enum GraphicOperation {
     DrawRectangle {
         rect: Rectangle,
@@ -16,7 +32,38 @@ pub enum GraphicOperation {
         text: String,
         color: Rgb888,
     },
+    DrawGradient {
+        rect: Rectangle,
+        gradient: LinearGradient,
+    },
+}
+
+impl GraphicOperation {
+    /// The same operation, moved by `offset`.
+    pub fn translated(self, offset: Point) -> Self {
+        match self {
+            GraphicOperation::DrawRectangle { rect, color } => GraphicOperation::DrawRectangle {
+                rect: Rectangle::new(rect.top_left + offset, rect.size),
+                color,
+            },
+            GraphicOperation::DrawText {
+                position,
+                text,
+                color,
+            } => GraphicOperation::DrawText {
+                position: position + offset,
+                text,
+                color,
+            },
+            GraphicOperation::DrawGradient { rect, gradient } => GraphicOperation::DrawGradient {
+                rect: Rectangle::new(rect.top_left + offset, rect.size),
+                gradient,
+            },
+        }
+    }
 }
+
+#[derive(Default)]
 pub struct GraphicOperationQueue {
     operations: Vec<GraphicOperation>,
 }
@@ -39,38 +86,134 @@ impl GraphicOperationQueue {
 
 pub trait GraphicsEndpoint {
     fn draw_queue(&mut self, queue: GraphicOperationQueue);
+
+    /// Logical size of the screen, if the endpoint draws to one.
+    fn size(&self) -> Option<Size> {
+        None
+    }
 }
 
-pub struct EmbeddedGraphicsEndpoint<T: DrawTarget<Color = Rgb888>>
-{
+/// Function used by an endpoint to turn the `Rgb888` colors of the operations
+/// into the native color of its target.
+pub type ColorMapping<C> = fn(Rgb888) -> C;
+
+/// Endpoint drawing into any `embedded_graphics` target.
+///
+/// Every color is converted once per operation with the endpoint color
+/// mapping, so displays using `Rgb565`, `Gray8` or `BinaryColor` can be used
+/// directly, without wrapping them in a `color_converted` target.
+pub struct EmbeddedGraphicsEndpoint<T: DrawTarget> {
     pub target: T,
+    color_mapping: ColorMapping<T::Color>,
+    dithering: bool,
 }
 
-impl<T: DrawTarget<Color = Rgb888>> EmbeddedGraphicsEndpoint<T> {
+impl<T> EmbeddedGraphicsEndpoint<T>
+where
+    T: DrawTarget,
+    T::Color: From<Rgb888> + 'static,
+{
+    /// Creates an endpoint using the `embedded_graphics` conversion from
+    /// `Rgb888` to the target color.
     pub fn new(target: T) -> Self {
-        Self { target }
+        Self::with_color_mapping(target, T::Color::from)
+    }
+}
+
+impl<T: DrawTarget> EmbeddedGraphicsEndpoint<T>
+where
+    T::Color: 'static,
+{
+    /// Creates an endpoint with a custom color mapping, for example to invert
+    /// a monochrome display or to choose the luma threshold of `BinaryColor`.
+    pub fn with_color_mapping(target: T, color_mapping: ColorMapping<T::Color>) -> Self {
+        Self {
+            target,
+            color_mapping,
+            dithering: false,
+        }
+    }
+
+    /// Enables ordered dithering of gradients, which hides the banding of
+    /// low bit depth targets like `Rgb565`, 8 bit 3-3-2 colors or monochrome
+    /// displays.
+    pub fn with_dithering(mut self, dithering: bool) -> Self {
+        self.dithering = dithering;
+        self
+    }
+
+    fn draw_gradient(&mut self, rect: Rectangle, gradient: LinearGradient) {
+        let length = match gradient.direction {
+            GradientDirection::Horizontal => rect.size.width,
+            GradientDirection::Vertical => rect.size.height,
+        };
+        let steps = quantization_steps::<T::Color>();
+
+        if let (true, Some(steps)) = (self.dithering, steps) {
+            let color_mapping = self.color_mapping;
+            let pixels = rect.points().map(|point| {
+                let index = match gradient.direction {
+                    GradientDirection::Horizontal => point.x - rect.top_left.x,
+                    GradientDirection::Vertical => point.y - rect.top_left.y,
+                };
+                let channels = gradient.channels_at(index as u32, length);
+                Pixel(
+                    point,
+                    color_mapping(dither(channels, point.x, point.y, steps)),
+                )
+            });
+            let _ = self.target.draw_iter(pixels);
+            return;
+        }
+
+        // without dithering each line across the gradient has a single color
+        for index in 0..length {
+            let line = match gradient.direction {
+                GradientDirection::Horizontal => Rectangle::new(
+                    rect.top_left + Point::new(index as i32, 0),
+                    Size::new(1, rect.size.height),
+                ),
+                GradientDirection::Vertical => Rectangle::new(
+                    rect.top_left + Point::new(0, index as i32),
+                    Size::new(rect.size.width, 1),
+                ),
+            };
+            let color = (self.color_mapping)(gradient.color_at(index, length));
+            let _ = self.target.fill_solid(&line, color);
+        }
     }
 }
 
-impl<T> GraphicsEndpoint for EmbeddedGraphicsEndpoint<T>
+impl<T: DrawTarget> GraphicsEndpoint for EmbeddedGraphicsEndpoint<T>
 where
-    T: DrawTarget<Color = Rgb888>,
+    T::Color: 'static,
 {
     fn draw_queue(&mut self, mut queue: GraphicOperationQueue) {
         for operation in queue.drain() {
             match operation {
                 GraphicOperation::DrawRectangle { rect, color } => {
-                    let _ = self.target.fill_solid(&rect, color);
+                    let _ = self.target.fill_solid(&rect, (self.color_mapping)(color));
                 }
                 GraphicOperation::DrawText {
                     position,
                     text,
                     color,
                 } => {
-                    let _ = Text::new(&text, position + Point::new(0, FONT_6X10.baseline as i32), MonoTextStyle::new(&FONT_6X10, color))
-                        .draw(&mut self.target);
+                    let _ = Text::new(
+                        &text,
+                        position + Point::new(0, FONT_6X10.baseline as i32),
+                        MonoTextStyle::new(&FONT_6X10, (self.color_mapping)(color)),
+                    )
+                    .draw(&mut self.target);
+                }
+                GraphicOperation::DrawGradient { rect, gradient } => {
+                    self.draw_gradient(rect, gradient);
                 }
             }
         }
     }
+
+    fn size(&self) -> Option<Size> {
+        Some(self.target.bounding_box().size)
+    }
 }
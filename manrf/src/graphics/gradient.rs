@@ -0,0 +1,213 @@
+use core::any::TypeId;
+
+use embedded_graphics::{
+    pixelcolor::{raw::RawData, Gray8, PixelColor, Rgb888},
+    prelude::RgbColor,
+};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum GradientDirection {
+    /// From the left edge to the right edge.
+    Horizontal,
+    /// From the top edge to the bottom edge.
+    Vertical,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct GradientStop {
+    /// Position of the stop along the gradient, from 0 to 100 percent.
+    pub position: u8,
+    pub color: Rgb888,
+}
+
+impl GradientStop {
+    pub const fn new(position: u8, color: Rgb888) -> Self {
+        Self { position, color }
+    }
+}
+
+/// Gradient between two or more color stops, sorted by position.
+///
+/// Stops are a static slice so gradients can be defined as constants, next to
+/// the palettes they use.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct LinearGradient {
+    pub direction: GradientDirection,
+    pub stops: &'static [GradientStop],
+}
+
+impl LinearGradient {
+    pub const fn horizontal(stops: &'static [GradientStop]) -> Self {
+        Self {
+            direction: GradientDirection::Horizontal,
+            stops,
+        }
+    }
+
+    pub const fn vertical(stops: &'static [GradientStop]) -> Self {
+        Self {
+            direction: GradientDirection::Vertical,
+            stops,
+        }
+    }
+
+    /// Color channels at `index` of a gradient `length` pixels long, in
+    /// 1/256 units to keep the precision needed for dithering.
+    pub(crate) fn channels_at(&self, index: u32, length: u32) -> [u32; 3] {
+        let (first, last) = match (self.stops.first(), self.stops.last()) {
+            (Some(first), Some(last)) => (first, last),
+            _ => return [0; 3],
+        };
+        // position along the gradient, in 1/256 percent units
+        let position = index * 100 * 256 / length.saturating_sub(1).max(1);
+
+        let start = first.position as u32 * 256;
+        let end = last.position as u32 * 256;
+        if position <= start {
+            return channels(first.color);
+        }
+        if position >= end {
+            return channels(last.color);
+        }
+
+        let (from, to) = self
+            .stops
+            .windows(2)
+            .map(|pair| (pair[0], pair[1]))
+            .find(|(_, to)| position <= to.position as u32 * 256)
+            .unwrap_or((*last, *last));
+        let from_position = from.position as u32 * 256;
+        let span = (to.position as u32 * 256)
+            .saturating_sub(from_position)
+            .max(1);
+        let weight = position - from_position;
+
+        let from = channels(from.color);
+        let to = channels(to.color);
+        [0, 1, 2].map(|c| (from[c] * (span - weight) + to[c] * weight) / span)
+    }
+
+    pub fn color_at(&self, index: u32, length: u32) -> Rgb888 {
+        let [r, g, b] = self
+            .channels_at(index, length)
+            .map(|c| ((c + 128) / 256).min(255) as u8);
+        Rgb888::new(r, g, b)
+    }
+}
+
+fn channels(color: Rgb888) -> [u32; 3] {
+    [color.r(), color.g(), color.b()].map(|c| c as u32 * 256)
+}
+
+const BAYER_4X4: [[u32; 4]; 4] = [[0, 8, 2, 10], [12, 4, 14, 6], [3, 11, 1, 9], [15, 7, 13, 5]];
+
+/// Difference between two representable values of each channel, in 8 bit
+/// units, for a target of color `C`. `None` means the target has enough depth
+/// and doesn't need dithering.
+pub(crate) fn quantization_steps<C: PixelColor + 'static>() -> Option<[u32; 3]> {
+    match C::Raw::BITS_PER_PIXEL {
+        1 => Some([255; 3]),
+        2 => Some([85; 3]),
+        4 => Some([17; 3]),
+        // 8 bit colors keep 3 bits for red and green and 2 for blue, but
+        // 8 bit grays are deep enough
+        8 if TypeId::of::<C>() == TypeId::of::<Gray8>() => None,
+        8 => Some([36, 36, 85]),
+        15 => Some([8; 3]),
+        // 565 formats keep one more bit for green
+        16 => Some([8, 4, 8]),
+        _ => None,
+    }
+}
+
+/// Applies a 4x4 ordered dither to channels in 1/256 units, for a target
+/// quantizing each channel in the given steps.
+pub(crate) fn dither(channels: [u32; 3], x: i32, y: i32, steps: [u32; 3]) -> Rgb888 {
+    let threshold = BAYER_4X4[y.rem_euclid(4) as usize][x.rem_euclid(4) as usize];
+    let [r, g, b] = [0, 1, 2].map(|c| {
+        // threshold centered around zero, between -step/2 and step/2
+        let offset = (2 * threshold + 1) * steps[c] * 256 / 32;
+        let half_step = steps[c] * 128;
+        (((channels[c] + offset).saturating_sub(half_step) + 128) / 256).min(255) as u8
+    });
+    Rgb888::new(r, g, b)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use embedded_graphics::pixelcolor::raw::RawU8;
+    use embedded_graphics::pixelcolor::{BinaryColor, Rgb565};
+
+    const STOPS: [GradientStop; 3] = [
+        GradientStop::new(0, Rgb888::BLACK),
+        GradientStop::new(50, Rgb888::new(200, 0, 0)),
+        GradientStop::new(100, Rgb888::new(200, 0, 100)),
+    ];
+
+    #[test]
+    fn interpolates_between_stops() {
+        let gradient = LinearGradient::vertical(&STOPS);
+        assert_eq!(gradient.color_at(0, 101), Rgb888::BLACK);
+        assert_eq!(gradient.color_at(25, 101), Rgb888::new(100, 0, 0));
+        assert_eq!(gradient.color_at(50, 101), Rgb888::new(200, 0, 0));
+        assert_eq!(gradient.color_at(75, 101), Rgb888::new(200, 0, 50));
+        assert_eq!(gradient.color_at(100, 101), Rgb888::new(200, 0, 100));
+    }
+
+    #[test]
+    fn dithering_keeps_the_average_color() {
+        let value = [100 * 256, 0, 255 * 256];
+        let pixels: Vec<_> = (0..4)
+            .flat_map(|y| (0..4).map(move |x| Rgb565::from(dither(value, x, y, [8, 4, 8]))))
+            .collect();
+        // 100 is between two Rgb565 red levels, so both must be used
+        let average_red = pixels.iter().map(|c| c.r() as u32).sum::<u32>() * 255 / 31 / 16;
+        assert!((97..=103).contains(&average_red));
+        assert!(pixels.iter().any(|c| c.r() != pixels[0].r()));
+        assert!(pixels.iter().all(|c| c.g() == 0 && c.b() == Rgb565::MAX_B));
+    }
+
+    /// 8 bit color of 3-3-2 panels.
+    #[derive(Clone, Copy, Debug, PartialEq)]
+    struct Rgb332(u8);
+
+    impl PixelColor for Rgb332 {
+        type Raw = RawU8;
+    }
+
+    impl Rgb332 {
+        fn from_rgb888(color: Rgb888) -> Self {
+            Self((color.r() >> 5) << 5 | (color.g() >> 5) << 2 | color.b() >> 6)
+        }
+
+        fn channels(self) -> [u32; 3] {
+            [
+                (self.0 >> 5) as u32,
+                (self.0 >> 2 & 7) as u32,
+                (self.0 & 3) as u32,
+            ]
+        }
+    }
+
+    #[test]
+    fn rgb332_targets_are_dithered() {
+        assert_eq!(quantization_steps::<Rgb332>(), Some([36, 36, 85]));
+        assert_eq!(quantization_steps::<Gray8>(), None);
+        assert_eq!(quantization_steps::<BinaryColor>(), Some([255; 3]));
+
+        let steps = quantization_steps::<Rgb332>().unwrap();
+        let value = [100 * 256, 0, 100 * 256];
+        let pixels: Vec<_> = (0..4)
+            .flat_map(|y| (0..4).map(move |x| Rgb332::from_rgb888(dither(value, x, y, steps))))
+            .map(Rgb332::channels)
+            .collect();
+        // 100 is between two levels of both the 3 bit red and the 2 bit blue
+        let average = |c: usize, max: u32| {
+            pixels.iter().map(|channels| channels[c]).sum::<u32>() * 255 / max / 16
+        };
+        assert!((90..=110).contains(&average(0, 7)));
+        assert!((90..=110).contains(&average(2, 3)));
+        assert!(pixels.iter().any(|channels| channels[2] != pixels[0][2]));
+    }
+}
@@ -0,0 +1,348 @@
+use embedded_graphics::{
+    prelude::{Point, Size},
+    primitives::Rectangle,
+};
+
+use super::{GraphicOperation, GraphicOperationQueue};
+
+/// Every extra fill costs about as much as this many pixels on SPI displays,
+/// because of the commands setting the address window, so splitting a
+/// rectangle is only worth it when it saves more than that per new fill.
+const FRAGMENT_COST_PIXELS: u64 = 8;
+
+/// Rectangles visible in more pieces than this are drawn whole, which keeps
+/// the cost of the pass bounded when many small fills cover a large one.
+const MAX_FRAGMENTS: usize = 32;
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct OptimizationStats {
+    pub operations_before: usize,
+    pub operations_after: usize,
+    /// Pixels written by fill operations before the optimization.
+    pub pixels_before: u64,
+    /// Pixels written by fill operations after the optimization.
+    pub pixels_after: u64,
+}
+
+impl OptimizationStats {
+    pub fn pixels_saved(&self) -> u64 {
+        self.pixels_before - self.pixels_after
+    }
+}
+
+impl GraphicOperationQueue {
+    /// Removes the pixels of fills that are painted over by later opaque
+    /// fills, and merges adjacent rectangles of the same color, without
+    /// changing the resulting image.
+    ///
+    /// The pass takes time quadratic in the number of operations, so it is
+    /// only worth it on displays where pixels are slow to write.
+    pub fn optimize(&mut self) -> OptimizationStats {
+        let operations_before = self.operations.len();
+        let pixels_before = fill_pixels(&self.operations);
+
+        self.remove_overdraw();
+        self.merge_rectangles();
+
+        OptimizationStats {
+            operations_before,
+            operations_after: self.operations.len(),
+            pixels_before,
+            pixels_after: fill_pixels(&self.operations),
+        }
+    }
+
+    fn remove_overdraw(&mut self) {
+        let mut covering: Vec<Rectangle> = Vec::new();
+        let mut optimized = Vec::with_capacity(self.operations.len());
+
+        // walking backwards, everything already seen is painted later
+        for operation in self.operations.drain(..).rev() {
+            match operation {
+                GraphicOperation::DrawRectangle { rect, color } => {
+                    covering.push(rect);
+                    let fragments = visible_fragments(rect, &covering[..covering.len() - 1])
+                        .filter(|fragments| {
+                            let saved = area(&rect) - fragments.iter().map(area).sum::<u64>();
+                            let added = fragments.len().saturating_sub(1) as u64;
+                            fragments.is_empty() || saved > added * FRAGMENT_COST_PIXELS
+                        });
+                    match fragments {
+                        Some(fragments) => optimized.extend(
+                            fragments
+                                .into_iter()
+                                .rev()
+                                .map(|rect| GraphicOperation::DrawRectangle { rect, color }),
+                        ),
+                        None => optimized.push(GraphicOperation::DrawRectangle { rect, color }),
+                    }
+                }
+                GraphicOperation::DrawGradient { rect, gradient } => {
+                    // gradients can't be split without changing them, only
+                    // dropped when they are not visible at all
+                    let hidden = visible_fragments(rect, &covering)
+                        .is_some_and(|fragments| fragments.is_empty());
+                    covering.push(rect);
+                    if !hidden {
+                        optimized.push(GraphicOperation::DrawGradient { rect, gradient });
+                    }
+                }
+                text @ GraphicOperation::DrawText { .. } => optimized.push(text),
+            }
+        }
+
+        optimized.reverse();
+        self.operations = optimized;
+    }
+
+    fn merge_rectangles(&mut self) {
+        // merges never look back before the last one, so every operation is
+        // the first of a pair at most once
+        let mut start = 0;
+        while let Some((first, second, merged)) = self.find_merge(start) {
+            self.operations[second] = GraphicOperation::DrawRectangle {
+                rect: merged,
+                color: fill_color(&self.operations[first]),
+            };
+            self.operations.remove(first);
+            start = first;
+        }
+    }
+
+    /// Finds two rectangles of the same color forming a rectangle together,
+    /// which can be drawn as one in the place of the second, the first one
+    /// being at `start` or after.
+    fn find_merge(&self, start: usize) -> Option<(usize, usize, Rectangle)> {
+        for (first, operation) in self.operations.iter().enumerate().skip(start) {
+            let (rect, color) = match operation {
+                GraphicOperation::DrawRectangle { rect, color } => (rect, color),
+                _ => continue,
+            };
+            for (offset, other) in self.operations[first + 1..].iter().enumerate() {
+                let second = first + 1 + offset;
+                if let GraphicOperation::DrawRectangle {
+                    rect: other_rect,
+                    color: other_color,
+                } = other
+                {
+                    if other_color == color {
+                        if let Some(merged) = union(rect, other_rect) {
+                            return Some((first, second, merged));
+                        }
+                    }
+                }
+                // the first rectangle can't be moved over anything drawn
+                // after it
+                if overlaps(rect, other) {
+                    break;
+                }
+            }
+        }
+        None
+    }
+}
+
+fn fill_color(operation: &GraphicOperation) -> embedded_graphics::pixelcolor::Rgb888 {
+    match operation {
+        GraphicOperation::DrawRectangle { color, .. } => *color,
+        _ => unreachable!(),
+    }
+}
+
+fn area(rect: &Rectangle) -> u64 {
+    rect.size.width as u64 * rect.size.height as u64
+}
+
+fn fill_pixels(operations: &[GraphicOperation]) -> u64 {
+    operations
+        .iter()
+        .map(|operation| match operation {
+            GraphicOperation::DrawRectangle { rect, .. } => area(rect),
+            GraphicOperation::DrawGradient { rect, .. } => area(rect),
+            GraphicOperation::DrawText { .. } => 0,
+        })
+        .sum()
+}
+
+fn overlaps(rect: &Rectangle, operation: &GraphicOperation) -> bool {
+    match operation {
+        GraphicOperation::DrawRectangle { rect: other, .. }
+        | GraphicOperation::DrawGradient { rect: other, .. } => {
+            !rect.intersection(other).is_zero_sized()
+        }
+        // the size of the text is not known, be conservative
+        GraphicOperation::DrawText { .. } => true,
+    }
+}
+
+/// Parts of `rect` not covered by any of the `covering` rectangles, or
+/// `None` when there are more than [`MAX_FRAGMENTS`] of them.
+fn visible_fragments(rect: Rectangle, covering: &[Rectangle]) -> Option<Vec<Rectangle>> {
+    let mut fragments = vec![rect];
+    for cover in covering {
+        fragments = fragments
+            .into_iter()
+            .flat_map(|fragment| subtract(fragment, cover))
+            .collect();
+        if fragments.is_empty() {
+            break;
+        }
+        if fragments.len() > MAX_FRAGMENTS {
+            return None;
+        }
+    }
+    Some(fragments)
+}
+
+/// Splits the part of `rect` outside `cover` in up to four rectangles.
+fn subtract(rect: Rectangle, cover: &Rectangle) -> Vec<Rectangle> {
+    let hole = rect.intersection(cover);
+    if hole.is_zero_sized() {
+        return vec![rect];
+    }
+
+    let (left, top) = (rect.top_left.x, rect.top_left.y);
+    let (right, bottom) = (left + rect.size.width as i32, top + rect.size.height as i32);
+    let (hole_left, hole_top) = (hole.top_left.x, hole.top_left.y);
+    let (hole_right, hole_bottom) = (
+        hole_left + hole.size.width as i32,
+        hole_top + hole.size.height as i32,
+    );
+
+    [
+        (left, top, right, hole_top),
+        (left, hole_bottom, right, bottom),
+        (left, hole_top, hole_left, hole_bottom),
+        (hole_right, hole_top, right, hole_bottom),
+    ]
+    .into_iter()
+    .filter(|(x0, y0, x1, y1)| x1 > x0 && y1 > y0)
+    .map(|(x0, y0, x1, y1)| {
+        Rectangle::new(
+            Point::new(x0, y0),
+            Size::new((x1 - x0) as u32, (y1 - y0) as u32),
+        )
+    })
+    .collect()
+}
+
+/// The rectangle covering exactly `a` and `b`, if there is one.
+fn union(a: &Rectangle, b: &Rectangle) -> Option<Rectangle> {
+    let same_columns = a.top_left.x == b.top_left.x && a.size.width == b.size.width;
+    let same_rows = a.top_left.y == b.top_left.y && a.size.height == b.size.height;
+    if same_columns {
+        let (upper, lower) = if a.top_left.y <= b.top_left.y {
+            (a, b)
+        } else {
+            (b, a)
+        };
+        if upper.top_left.y + upper.size.height as i32 == lower.top_left.y {
+            return Some(Rectangle::new(
+                upper.top_left,
+                Size::new(upper.size.width, upper.size.height + lower.size.height),
+            ));
+        }
+    }
+    if same_rows {
+        let (left, right) = if a.top_left.x <= b.top_left.x {
+            (a, b)
+        } else {
+            (b, a)
+        };
+        if left.top_left.x + left.size.width as i32 == right.top_left.x {
+            return Some(Rectangle::new(
+                left.top_left,
+                Size::new(left.size.width + right.size.width, left.size.height),
+            ));
+        }
+    }
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use embedded_graphics::pixelcolor::{Rgb888, RgbColor};
+
+    use super::*;
+
+    fn fill(x: i32, y: i32, width: u32, height: u32, color: Rgb888) -> GraphicOperation {
+        GraphicOperation::DrawRectangle {
+            rect: Rectangle::new(Point::new(x, y), Size::new(width, height)),
+            color,
+        }
+    }
+
+    fn optimized(operations: Vec<GraphicOperation>) -> (Vec<GraphicOperation>, OptimizationStats) {
+        let mut queue = GraphicOperationQueue::new();
+        for operation in operations {
+            queue.push(operation);
+        }
+        let stats = queue.optimize();
+        (queue.drain().collect(), stats)
+    }
+
+    #[test]
+    fn removes_rectangles_covered_by_later_ones() {
+        let (operations, stats) = optimized(vec![
+            fill(10, 10, 5, 5, Rgb888::RED),
+            fill(0, 0, 20, 20, Rgb888::BLUE),
+        ]);
+        assert_eq!(operations, vec![fill(0, 0, 20, 20, Rgb888::BLUE)]);
+        assert_eq!(stats.pixels_saved(), 25);
+    }
+
+    #[test]
+    fn trims_partially_covered_rectangles() {
+        let (operations, stats) = optimized(vec![
+            fill(0, 0, 20, 20, Rgb888::RED),
+            fill(0, 10, 20, 10, Rgb888::BLUE),
+        ]);
+        assert_eq!(
+            operations,
+            vec![
+                fill(0, 0, 20, 10, Rgb888::RED),
+                fill(0, 10, 20, 10, Rgb888::BLUE)
+            ]
+        );
+        assert_eq!(stats.pixels_saved(), 200);
+    }
+
+    #[test]
+    fn merges_adjacent_rectangles_of_the_same_color() {
+        let (operations, _) = optimized(vec![
+            fill(0, 0, 10, 5, Rgb888::RED),
+            fill(0, 5, 10, 5, Rgb888::RED),
+            fill(10, 0, 5, 10, Rgb888::RED),
+        ]);
+        assert_eq!(operations, vec![fill(0, 0, 15, 10, Rgb888::RED)]);
+    }
+
+    #[test]
+    fn rectangles_visible_in_many_pieces_are_kept_whole() {
+        let mut operations = vec![fill(0, 0, 160, 160, Rgb888::RED)];
+        // a grid of holes in the red rectangle
+        for row in 0..8 {
+            for column in 0..8 {
+                operations.push(fill(column * 20 + 5, row * 20 + 5, 10, 10, Rgb888::BLUE));
+            }
+        }
+        let (optimized, _) = optimized(operations.clone());
+        assert_eq!(optimized[0], operations[0]);
+        assert_eq!(optimized.len(), operations.len());
+    }
+
+    #[test]
+    fn keeps_text_painted_between_rectangles() {
+        let text = GraphicOperation::DrawText {
+            position: Point::new(0, 0),
+            text: "Hi".to_string(),
+            color: Rgb888::WHITE,
+        };
+        let operations = vec![
+            fill(0, 0, 10, 5, Rgb888::RED),
+            text,
+            fill(0, 5, 10, 5, Rgb888::RED),
+        ];
+        assert_eq!(optimized(operations.clone()).0, operations);
+    }
+}
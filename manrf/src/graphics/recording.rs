@@ -0,0 +1,138 @@
+use core::fmt;
+
+use embedded_graphics::{pixelcolor::Rgb888, prelude::RgbColor};
+
+use super::{GradientDirection, GraphicOperation, GraphicOperationQueue, GraphicsEndpoint};
+
+/// Stable textual form of an operation, one line per operation:
+///
+/// ```text
+/// rect 4,12 127x14 #32d9ce
+/// text 10,16 #ffffff "Key: Second 2"
+/// gradient 0,0 135x20 Vertical 0:#3f0d68 100:#f41c7d
+/// ```
+impl fmt::Display for GraphicOperation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            GraphicOperation::DrawRectangle { rect, color } => write!(
+                f,
+                "rect {},{} {}x{} {}",
+                rect.top_left.x,
+                rect.top_left.y,
+                rect.size.width,
+                rect.size.height,
+                HexColor(*color)
+            ),
+            GraphicOperation::DrawText {
+                position,
+                text,
+                color,
+            } => write!(
+                f,
+                "text {},{} {} {:?}",
+                position.x,
+                position.y,
+                HexColor(*color),
+                text
+            ),
+            GraphicOperation::DrawGradient { rect, gradient } => {
+                write!(
+                    f,
+                    "gradient {},{} {}x{} {:?}",
+                    rect.top_left.x,
+                    rect.top_left.y,
+                    rect.size.width,
+                    rect.size.height,
+                    gradient.direction
+                )?;
+                for stop in gradient.stops {
+                    write!(f, " {}:{}", stop.position, HexColor(stop.color))?;
+                }
+                Ok(())
+            }
+        }
+    }
+}
+
+struct HexColor(Rgb888);
+
+impl fmt::Display for HexColor {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "#{:02x}{:02x}{:02x}", self.0.r(), self.0.g(), self.0.b())
+    }
+}
+
+/// Endpoint that keeps every drawn operation instead of drawing pixels, to
+/// assert on the output of an app without any display.
+#[derive(Default)]
+pub struct RecordingEndpoint {
+    frames: Vec<Vec<GraphicOperation>>,
+}
+
+impl RecordingEndpoint {
+    pub fn new() -> Self {
+        Self { frames: Vec::new() }
+    }
+
+    pub fn frames(&self) -> &[Vec<GraphicOperation>] {
+        &self.frames
+    }
+
+    /// Operations of the last drawn frame, empty if nothing was drawn yet.
+    pub fn last_frame(&self) -> &[GraphicOperation] {
+        self.frames.last().map(Vec::as_slice).unwrap_or_default()
+    }
+
+    /// Textual form of the last frame, one operation per line.
+    pub fn last_frame_text(&self) -> String {
+        self.last_frame()
+            .iter()
+            .map(|operation| operation.to_string())
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+
+    /// Index in the last frame of the first text operation drawing `text`.
+    pub fn find_text(&self, text: &str) -> Option<usize> {
+        self.last_frame().iter().position(|operation| {
+            matches!(operation, GraphicOperation::DrawText { text: drawn, .. } if drawn == text)
+        })
+    }
+
+    /// Color of the last rectangle drawn under the position of `text` before
+    /// the text itself, in the last frame.
+    pub fn color_behind_text(&self, text: &str) -> Option<Rgb888> {
+        let index = self.find_text(text)?;
+        let frame = self.last_frame();
+        let position = match &frame[index] {
+            GraphicOperation::DrawText { position, .. } => *position,
+            _ => unreachable!(),
+        };
+        frame[..index]
+            .iter()
+            .rev()
+            .find_map(|operation| match operation {
+                GraphicOperation::DrawRectangle { rect, color } if rect.contains(position) => {
+                    Some(*color)
+                }
+                GraphicOperation::DrawGradient { rect, gradient } if rect.contains(position) => {
+                    let offset = position - rect.top_left;
+                    Some(match gradient.direction {
+                        GradientDirection::Horizontal => {
+                            gradient.color_at(offset.x as u32, rect.size.width)
+                        }
+                        GradientDirection::Vertical => {
+                            gradient.color_at(offset.y as u32, rect.size.height)
+                        }
+                    })
+                }
+                _ => None,
+            })
+    }
+}
+
+impl GraphicsEndpoint for RecordingEndpoint {
+    fn draw_queue(&mut self, mut queue: GraphicOperationQueue) {
+        self.frames.push(queue.drain().collect());
+    }
+}
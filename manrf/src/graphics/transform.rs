@@ -0,0 +1,183 @@
+use embedded_graphics::{
+    prelude::{DrawTarget, OriginDimensions, Point, Size},
+    primitives::Rectangle,
+    Pixel,
+};
+
+/// Clockwise rotation of the image on the physical panel.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Rotation {
+    #[default]
+    Deg0,
+    Deg90,
+    Deg180,
+    Deg270,
+}
+
+/// How the logical screen the app lays out against is mounted on the panel.
+///
+/// Mirroring flips the logical screen horizontally before rotating it.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct Transform {
+    pub rotation: Rotation,
+    pub mirror: bool,
+}
+
+impl Transform {
+    pub const fn rotate(rotation: Rotation) -> Self {
+        Self {
+            rotation,
+            mirror: false,
+        }
+    }
+
+    pub const fn mirrored(self) -> Self {
+        Self {
+            rotation: self.rotation,
+            mirror: !self.mirror,
+        }
+    }
+
+    /// Size of the logical screen for a panel of `physical` size.
+    pub const fn logical_size(&self, physical: Size) -> Size {
+        match self.rotation {
+            Rotation::Deg0 | Rotation::Deg180 => physical,
+            Rotation::Deg90 | Rotation::Deg270 => Size::new(physical.height, physical.width),
+        }
+    }
+
+    /// Maps a logical point to the panel of `physical` size.
+    pub fn point(&self, point: Point, physical: Size) -> Point {
+        let logical = self.logical_size(physical);
+        let x = if self.mirror {
+            logical.width as i32 - 1 - point.x
+        } else {
+            point.x
+        };
+        let y = point.y;
+        let (width, height) = (physical.width as i32, physical.height as i32);
+        match self.rotation {
+            Rotation::Deg0 => Point::new(x, y),
+            Rotation::Deg90 => Point::new(width - 1 - y, x),
+            Rotation::Deg180 => Point::new(width - 1 - x, height - 1 - y),
+            Rotation::Deg270 => Point::new(y, height - 1 - x),
+        }
+    }
+
+    /// Maps a logical rectangle to the panel of `physical` size.
+    pub fn rectangle(&self, rect: &Rectangle, physical: Size) -> Rectangle {
+        match rect.bottom_right() {
+            Some(bottom_right) => Rectangle::with_corners(
+                self.point(rect.top_left, physical),
+                self.point(bottom_right, physical),
+            ),
+            None => Rectangle::new(self.point(rect.top_left, physical), Size::zero()),
+        }
+    }
+}
+
+/// Draw target applying a `Transform` to everything drawn on it, so it can
+/// be used by any endpoint while the app only sees the logical size.
+pub struct TransformedTarget<T> {
+    target: T,
+    transform: Transform,
+}
+
+impl<T: DrawTarget> TransformedTarget<T> {
+    pub fn new(target: T, transform: Transform) -> Self {
+        Self { target, transform }
+    }
+
+    pub fn inner(&self) -> &T {
+        &self.target
+    }
+
+    pub fn inner_mut(&mut self) -> &mut T {
+        &mut self.target
+    }
+
+    pub fn into_inner(self) -> T {
+        self.target
+    }
+
+    fn physical_size(&self) -> Size {
+        self.target.bounding_box().size
+    }
+}
+
+impl<T: DrawTarget> DrawTarget for TransformedTarget<T> {
+    type Color = T::Color;
+    type Error = T::Error;
+
+    fn draw_iter<I>(&mut self, pixels: I) -> Result<(), Self::Error>
+    where
+        I: IntoIterator<Item = Pixel<Self::Color>>,
+    {
+        let physical = self.physical_size();
+        let transform = self.transform;
+        self.target.draw_iter(
+            pixels
+                .into_iter()
+                .map(|Pixel(point, color)| Pixel(transform.point(point, physical), color)),
+        )
+    }
+
+    fn fill_solid(&mut self, area: &Rectangle, color: Self::Color) -> Result<(), Self::Error> {
+        // rectangles stay rectangles, keep the fast path of the target
+        let area = self.transform.rectangle(area, self.physical_size());
+        self.target.fill_solid(&area, color)
+    }
+
+    fn clear(&mut self, color: Self::Color) -> Result<(), Self::Error> {
+        self.target.clear(color)
+    }
+}
+
+impl<T: DrawTarget> OriginDimensions for TransformedTarget<T> {
+    fn size(&self) -> Size {
+        self.transform.logical_size(self.physical_size())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const PHYSICAL: Size = Size::new(4, 3);
+
+    #[test]
+    fn maps_corners_of_the_logical_screen() {
+        let cases = [
+            (Transform::rotate(Rotation::Deg0), Point::new(0, 0)),
+            (Transform::rotate(Rotation::Deg90), Point::new(3, 0)),
+            (Transform::rotate(Rotation::Deg180), Point::new(3, 2)),
+            (Transform::rotate(Rotation::Deg270), Point::new(0, 2)),
+            (
+                Transform::rotate(Rotation::Deg0).mirrored(),
+                Point::new(3, 0),
+            ),
+            (
+                Transform::rotate(Rotation::Deg90).mirrored(),
+                Point::new(3, 2),
+            ),
+        ];
+        for (transform, top_left) in cases {
+            assert_eq!(
+                transform.point(Point::zero(), PHYSICAL),
+                top_left,
+                "{:?}",
+                transform
+            );
+        }
+    }
+
+    #[test]
+    fn rotated_rectangles_keep_their_area() {
+        let transform = Transform::rotate(Rotation::Deg90);
+        let rect = Rectangle::new(Point::new(1, 0), Size::new(2, 3));
+        assert_eq!(
+            transform.rectangle(&rect, PHYSICAL),
+            Rectangle::new(Point::new(1, 1), Size::new(3, 2))
+        );
+    }
+}
@@ -0,0 +1,135 @@
+//! Lenses bind stateful widgets and nested screens to a part of the state.
+
+use core::marker::PhantomData;
+
+use crate::utils::Rc;
+
+/// Focuses on a part `T` of a state `S`, to read it and change it in place.
+///
+/// Lenses to nested parts are made by composing them with [`Lens::then`],
+/// or in one step with [`lens!`](crate::lens!).
+pub trait Lens<S, T>: 'static {
+    fn get<'a>(&self, source: &'a S) -> &'a T;
+
+    fn get_mut<'a>(&self, source: &'a mut S) -> &'a mut T;
+
+    /// Focuses on a part `U` of the part of this lens.
+    fn then<U, L: Lens<T, U>>(self, next: L) -> Then<Self, L, T>
+    where
+        Self: Sized,
+        T: 'static,
+    {
+        Then {
+            first: self,
+            second: next,
+            middle: PhantomData,
+        }
+    }
+}
+
+impl<S, T, L: Lens<S, T> + ?Sized> Lens<S, T> for Rc<L> {
+    fn get<'a>(&self, source: &'a S) -> &'a T {
+        (**self).get(source)
+    }
+
+    fn get_mut<'a>(&self, source: &'a mut S) -> &'a mut T {
+        (**self).get_mut(source)
+    }
+}
+
+/// Lens made of accessor functions, usually with [`lens!`](crate::lens!).
+pub struct Field<S, T> {
+    get: fn(&S) -> &T,
+    get_mut: fn(&mut S) -> &mut T,
+}
+
+impl<S, T> Field<S, T> {
+    pub fn new(get: fn(&S) -> &T, get_mut: fn(&mut S) -> &mut T) -> Self {
+        Self { get, get_mut }
+    }
+}
+
+impl<S: 'static, T: 'static> Lens<S, T> for Field<S, T> {
+    fn get<'a>(&self, source: &'a S) -> &'a T {
+        (self.get)(source)
+    }
+
+    fn get_mut<'a>(&self, source: &'a mut S) -> &'a mut T {
+        (self.get_mut)(source)
+    }
+}
+
+/// Lens focusing on the whole state.
+pub struct Identity;
+
+impl<S> Lens<S, S> for Identity {
+    fn get<'a>(&self, source: &'a S) -> &'a S {
+        source
+    }
+
+    fn get_mut<'a>(&self, source: &'a mut S) -> &'a mut S {
+        source
+    }
+}
+
+/// Composition of two lenses, made with [`Lens::then`].
+pub struct Then<A, B, T> {
+    first: A,
+    second: B,
+    middle: PhantomData<fn() -> T>,
+}
+
+impl<S, T: 'static, U, A: Lens<S, T>, B: Lens<T, U>> Lens<S, U> for Then<A, B, T> {
+    fn get<'a>(&self, source: &'a S) -> &'a U {
+        self.second.get(self.first.get(source))
+    }
+
+    fn get_mut<'a>(&self, source: &'a mut S) -> &'a mut U {
+        self.second.get_mut(self.first.get_mut(source))
+    }
+}
+
+/// Lens to a field of a state, which can be nested.
+///
+/// ```ignore
+/// let volume = lens!(AppState, settings.audio.volume);
+/// ```
+#[macro_export]
+macro_rules! lens {
+    ($state:ty, $($field:tt).+) => {
+        $crate::lens::Field::new(
+            |state: &$state| &state.$($field).+,
+            |state: &mut $state| &mut state.$($field).+,
+        )
+    };
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[derive(Default)]
+    struct Settings {
+        volume: u8,
+    }
+
+    #[derive(Default)]
+    struct AppState {
+        settings: Settings,
+        pair: (u8, u8),
+    }
+
+    #[test]
+    fn lenses_compose() {
+        let mut state = AppState::default();
+        let settings = lens!(AppState, settings);
+        let volume = settings.then(lens!(Settings, volume));
+        *volume.get_mut(&mut state) = 7;
+        assert_eq!(state.settings.volume, 7);
+        assert_eq!(*lens!(AppState, settings.volume).get(&state), 7);
+
+        let second = Rc::new(lens!(AppState, pair.1));
+        *Identity.then(second).get_mut(&mut state) = 3;
+        assert_eq!(state.pair, (0, 3));
+    }
+}
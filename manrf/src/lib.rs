@@ -1,13 +1,28 @@
+use core::time::Duration;
 use defs::*;
-use graphics::{GraphicOperation, GraphicOperationQueue, GraphicsEndpoint};
+use event::channel::{Inbox, Posted};
+use event::{EventSender, InputSource, InputTiming, InputTracker};
+use focus::FocusTraversal;
+use graphics::{GraphicOperation, GraphicOperationQueue, GraphicsEndpoint, OptimizationStats};
+use lens::Lens;
+use pointer::{GestureConfig, GestureRecognizer};
+use std::cell::RefCell;
 use utils::*;
 
+mod cache;
 pub mod defs;
 pub mod elements;
 pub mod event;
+pub mod focus;
 
 pub mod graphics;
+pub mod lens;
+pub mod navigation;
 pub mod palette;
+pub mod pointer;
+#[cfg(any(test, feature = "screenshot"))]
+pub mod screenshot;
+pub mod store;
 
 pub mod utils;
 pub struct Stack<S> {
@@ -157,39 +172,59 @@ pub struct ItemSelectorState {
     pub active: usize,
     pub selected: Option<usize>,
 }
+type RenderItem<S, V> = Rc<dyn Fn(&V, bool) -> Element<S>>;
+
 pub struct ItemSelector<S, V> {
-    items_lookup: fn(&S) -> &Vec<V>,
-    selector_state_lookup: fn(&S) -> ItemSelectorState,
-    set_selector_state: fn(&mut S, ItemSelectorState),
-    render_item: fn(&V, bool) -> Element<S>,
+    items: Rc<dyn Lens<S, Vec<V>>>,
+    selector_state: Rc<dyn Lens<S, ItemSelectorState>>,
+    render_item: RenderItem<S, V>,
+    // bounds of the items in the last render, to find the tapped one
+    item_bounds: RefCell<Vec<Rectangle>>,
 }
 
 impl<S, V> ItemSelector<S, V> {
+    /// A list of the `items`, whose active and selected items are kept in
+    /// `selector_state`.
+    ///
+    /// ```ignore
+    /// ItemSelector::new(lens!(AppState, keys), lens!(AppState, keys_state), render_key)
+    /// ```
     pub fn new(
-        items_lookup: fn(&S) -> &Vec<V>,
-        selector_state_lookup: fn(&S) -> ItemSelectorState,
-        set_selector_state: fn(&mut S, ItemSelectorState),
-        render_item: fn(&V, bool) -> Element<S>,
+        items: impl Lens<S, Vec<V>>,
+        selector_state: impl Lens<S, ItemSelectorState>,
+        render_item: impl Fn(&V, bool) -> Element<S> + 'static,
     ) -> Rc<Self> {
         Rc::new(Self {
-            items_lookup,
-            selector_state_lookup,
-            set_selector_state,
-            render_item,
+            items: Rc::new(items),
+            selector_state: Rc::new(selector_state),
+            render_item: Rc::new(render_item),
+            item_bounds: RefCell::new(Vec::new()),
         })
     }
 }
 
-impl<S: State, V> ElementTrait<S> for ItemSelector<S, V> {
+impl<S, V> ItemSelector<S, V> {
+    fn item_at(&self, point: Point) -> Option<usize> {
+        self.item_bounds
+            .borrow()
+            .iter()
+            .position(|bounds| bounds.contains(point))
+    }
+}
+
+impl<S: State, V: 'static> ElementTrait<S> for ItemSelector<S, V> {
     fn render(&self, constraints: Constraints, state: &S) -> (Size, RenderNode<S>) {
         let mut size = Size::new(0, 0);
         let mut children = Vec::new();
-        let items = (self.items_lookup)(state);
-        let element_state = (self.selector_state_lookup)(state);
+        let items = self.items.get(state);
+        let element_state = self.selector_state.get(state);
+        let mut item_bounds = self.item_bounds.borrow_mut();
+        item_bounds.clear();
         for (index, item) in items.iter().enumerate() {
             let active = index == element_state.active;
             let render = (self.render_item)(item, active);
             let child = render.render(constraints, state);
+            item_bounds.push(Rectangle::new(Point::new(0, size.height as i32), child.0));
             children.push(RenderNode::SingleChild {
                 offset: Point::new(0, size.height as i32),
                 size: child.0,
@@ -209,115 +244,510 @@ impl<S: State, V> ElementTrait<S> for ItemSelector<S, V> {
         )
     }
 
-    fn event_handler(&self, state: &mut S, event: event::Event) -> bool {
-        let mut element_state = (self.selector_state_lookup)(state);
+    fn focusable(&self) -> bool {
+        true
+    }
+
+    // moving past the first or last item is left unhandled, so focus can move
+    // to the next focusable element
+    fn event_handler(&self, state: &mut S, event: event::Event) -> EventResponse {
+        let Some(last) = self.items.get(state).len().checked_sub(1) else {
+            // an empty list has nothing to activate or select
+            return EventResponse::Ignored;
+        };
+        let element_state = self.selector_state.get_mut(state);
         match event {
             Event::DirectionPressed(Direction::Up) => {
-                if element_state.active > 0 {
-                    element_state.active -= 1;
-                    (self.set_selector_state)(state, element_state);
+                if element_state.active == 0 {
+                    return EventResponse::Ignored;
                 }
-                true
+                element_state.active -= 1;
+                EventResponse::Changed
             }
             Event::DirectionPressed(Direction::Down) => {
-                let items = (self.items_lookup)(state);
-                if element_state.active < items.len() - 1 {
-                    element_state.active += 1;
-                    (self.set_selector_state)(state, element_state);
+                if element_state.active >= last {
+                    return EventResponse::Ignored;
                 }
-                true
+                element_state.active += 1;
+                EventResponse::Changed
             }
             Event::ButtonPressed(Button::Principal) => {
-                element_state.selected = Some(element_state.active);
-                (self.set_selector_state)(state, element_state);
-                true
+                let selected = Some(element_state.active);
+                EventResponse::handled(replace_if_changed(&mut element_state.selected, selected))
             }
-            _ => false,
+            Event::EncoderRotated(steps) => {
+                let active = element_state.active as i64 + steps as i64;
+                let active = active.clamp(0, last as i64) as usize;
+                if active == element_state.active {
+                    return EventResponse::Ignored;
+                }
+                element_state.active = active;
+                EventResponse::Changed
+            }
+            // pressing an item activates it, releasing it on the same item selects it
+            Event::PointerDown(point) => match self.item_at(point) {
+                Some(index) => {
+                    EventResponse::handled(replace_if_changed(&mut element_state.active, index))
+                }
+                None => EventResponse::Ignored,
+            },
+            Event::PointerUp(point) => match self.item_at(point) {
+                Some(index) if index == element_state.active => EventResponse::handled(
+                    replace_if_changed(&mut element_state.selected, Some(index)),
+                ),
+                Some(_) => EventResponse::Consumed,
+                None => EventResponse::Ignored,
+            },
+            _ => EventResponse::Ignored,
         }
     }
 }
 
+/// Number of contents, like routes, whose focus is kept after they are
+/// left.
+const MAX_SAVED_FOCUS: usize = 16;
+
+/// Sets `value` to `new`, returning whether it changed.
+fn replace_if_changed<T: PartialEq>(value: &mut T, new: T) -> bool {
+    let changed = *value != new;
+    *value = new;
+    changed
+}
+
 pub struct App<S: State, U: GraphicsEndpoint> {
     state: S,
     root: Element<S>,
     last_render_tree: RenderNode<S>,
     inital_size: Size,
+    focus: Option<usize>,
+    focus_traversal: FocusTraversal,
+    // focus keys of the last render, and the focus saved for other keys
+    focus_keys: Vec<u64>,
+    saved_focus: Vec<(Vec<u64>, Option<usize>)>,
+    input: InputTracker,
+    gestures: GestureRecognizer,
+    inbox: Inbox<S>,
+    shortcuts: Vec<(Phase, SharedEventHandler<S>)>,
+    // elements of the last render wanting ticks
+    tickers: Vec<Element<S>>,
+    render_cache: cache::RenderCache,
+    optimize: bool,
+    optimization_stats: OptimizationStats,
+    // whether the last render wasn't drawn yet
+    needs_draw: bool,
     // this is public because the simulator needs to access it, but it should not be public
     pub endpoint: U,
 }
 
 impl<S: State, U: GraphicsEndpoint> App<S, U> {
-    pub fn new(root: ComponentGenerator<S>, inital_size: Size, endpoint: U) -> Self {
-        let state = S::default();
-        let root = crate::elements::Component::new(root);
-        let last_render_tree = root
-            .render(
-                Constraints {
-                    min: Size::zero(),
-                    max: inital_size,
-                },
-                &state,
-            )
-            .1;
-
-        Self {
-            root,
-            state,
+    pub fn new(root: impl Fn(&S) -> Element<S> + 'static, inital_size: Size, endpoint: U) -> Self {
+        let mut app = Self {
+            root: crate::elements::Component::new(root),
+            state: S::default(),
             inital_size,
-            last_render_tree,
+            last_render_tree: RenderNode::Leaf,
+            focus: None,
+            focus_traversal: FocusTraversal::default(),
+            focus_keys: Vec::new(),
+            saved_focus: Vec::new(),
+            input: InputTracker::default(),
+            gestures: GestureRecognizer::default(),
+            inbox: Default::default(),
+            shortcuts: Vec::new(),
+            tickers: Vec::new(),
+            render_cache: Default::default(),
+            optimize: false,
+            optimization_stats: OptimizationStats::default(),
+            needs_draw: true,
             endpoint,
+        };
+        app.render();
+        app
+    }
+
+    /// Creates an app laying out against the whole screen of the endpoint,
+    /// which is the logical size when the endpoint target is transformed.
+    ///
+    /// Panics if the endpoint doesn't know its size.
+    pub fn fullscreen(root: impl Fn(&S) -> Element<S> + 'static, endpoint: U) -> Self {
+        let size = endpoint
+            .size()
+            .expect("the endpoint doesn't know the size of its screen");
+        Self::new(root, size, endpoint)
+    }
+
+    /// Removes the overdraw of every frame before drawing it, with
+    /// [`GraphicOperationQueue::optimize`]. It is off by default, as it only
+    /// pays off on displays where pixels are slow to write, like SPI panels.
+    pub fn set_optimize(&mut self, optimize: bool) {
+        self.optimize = optimize;
+    }
+
+    /// Overdraw statistics of the last drawn frame, when it was optimized.
+    pub fn optimization_stats(&self) -> OptimizationStats {
+        self.optimization_stats
+    }
+
+    pub fn state(&self) -> &S {
+        &self.state
+    }
+
+    pub fn set_focus_traversal(&mut self, traversal: FocusTraversal) {
+        self.focus_traversal = traversal;
+    }
+
+    pub fn set_input_timing(&mut self, timing: InputTiming) {
+        self.input.set_timing(timing);
+    }
+
+    pub fn set_gesture_config(&mut self, config: GestureConfig) {
+        self.gestures.set_config(config);
+    }
+
+    /// Handles a raw press, release or pointer event from the device at
+    /// `now`, ignoring the presses of keys already held. Pointer events are
+    /// followed by the gesture they complete.
+    ///
+    /// [`App::poll_input`] has to be called regularly afterwards for held
+    /// keys to repeat and long press.
+    pub fn handle_input(&mut self, event: event::Event, now: Duration) {
+        self.poll_input(now);
+        if let Some(event) = self.input.process(event, now) {
+            let gesture = self.gestures.process(&event, now);
+            self.handle_event(event);
+            if let Some(gesture) = gesture {
+                self.handle_event(Event::Gesture(gesture));
+            }
         }
     }
 
-    fn handle_event_recursive(&mut self, event: event::Event, render_root: &RenderNode<S>) -> bool {
-        match render_root {
+    /// Registers a handler for the whole app. In the capture phase it runs
+    /// before any element gets the event, and in the bubble phase when none
+    /// of them handled it, before focus moves.
+    ///
+    /// Shortcuts of the same phase run in the order they were added, until
+    /// one handles the event.
+    pub fn add_shortcut<R: Into<EventResponse>>(
+        &mut self,
+        phase: Phase,
+        handler: impl Fn(&mut S, Event) -> R + 'static,
+    ) {
+        self.shortcuts.push((
+            phase,
+            Rc::new(move |state: &mut S, event| handler(state, event).into()),
+        ));
+    }
+
+    fn run_shortcuts(&mut self, phase: Phase, event: &event::Event) -> EventResponse {
+        self.shortcuts
+            .iter()
+            .filter(|(shortcut_phase, _)| *shortcut_phase == phase)
+            .map(|(_, handler)| handler(&mut self.state, event.clone()))
+            .find(|response| response.is_handled())
+            .unwrap_or_default()
+    }
+
+    /// Handle for other threads to post events and state updates, handled on
+    /// the next tick.
+    pub fn sender(&self) -> EventSender<S> {
+        self.inbox.sender()
+    }
+
+    /// Handles the posts of the senders, rendering once after consecutive
+    /// updates.
+    fn handle_posted(&mut self) {
+        let mut updated = false;
+        while let Some(posted) = self.inbox.try_recv() {
+            match posted {
+                Posted::Update(update) => {
+                    update(&mut self.state);
+                    updated = true;
+                }
+                Posted::Event(event) => {
+                    if core::mem::take(&mut updated) {
+                        self.render();
+                    }
+                    self.handle_event(event);
+                }
+            }
+        }
+        if updated {
+            self.render();
+        }
+    }
+
+    /// Handles all the events the source has at `now`.
+    pub fn poll_source(&mut self, source: &mut impl InputSource, now: Duration) {
+        while let Some(event) = source.poll_event(now) {
+            self.handle_input(event, now);
+        }
+    }
+
+    /// Sends the repeats and long presses of the held keys and pointer due
+    /// at `now`.
+    pub fn poll_input(&mut self, now: Duration) {
+        for event in self.input.poll(now) {
+            self.handle_event(event);
+        }
+        if let Some(gesture) = self.gestures.poll(now) {
+            self.handle_event(Event::Gesture(gesture));
+        }
+    }
+
+    /// Absolute bounds of the focused element.
+    pub fn focused_bounds(&self) -> Option<Rectangle> {
+        let focusables = focus::focusables(&self.last_render_tree);
+        self.focus.map(|index| focusables[index].bounds)
+    }
+
+    fn render(&mut self) {
+        let constraints = Constraints {
+            min: Size::zero(),
+            max: self.inital_size,
+        };
+        self.last_render_tree = cache::with_cache(&mut self.render_cache, || {
+            self.root.render(constraints, &self.state).1
+        });
+        self.tickers.clear();
+        collect_tickers(&self.last_render_tree, &mut self.tickers);
+        self.needs_draw = true;
+
+        let mut keys = Vec::new();
+        focus::focus_keys(&self.last_render_tree, &mut keys);
+        if keys != self.focus_keys {
+            self.switch_focus(keys);
+        }
+        // focus is kept by position in render order, and the first
+        // focusable element gets it when nothing had it
+        let count = focus::focusables(&self.last_render_tree).len();
+        self.focus = match count {
+            0 => None,
+            _ => Some(self.focus.unwrap_or(0).min(count - 1)),
+        };
+    }
+
+    /// Saves the focus of the content shown until now, and restores the one
+    /// of the content identified by `keys`.
+    fn switch_focus(&mut self, keys: Vec<u64>) {
+        let previous = core::mem::replace(&mut self.focus_keys, keys);
+        self.saved_focus.push((previous, self.focus));
+        self.focus = self
+            .saved_focus
+            .iter()
+            .position(|(keys, _)| *keys == self.focus_keys)
+            .and_then(|index| self.saved_focus.remove(index).1);
+        // the focus saved for popped routes is never restored, so the
+        // oldest is dropped
+        if self.saved_focus.len() > MAX_SAVED_FOCUS {
+            self.saved_focus.remove(0);
+        }
+    }
+
+    /// Dispatches the event to the whole tree, when no element is focused:
+    /// capture from the root down, then bubble from the leaves up, visiting
+    /// the children in render order.
+    fn handle_event_recursive(
+        &mut self,
+        event: event::Event,
+        render_root: &RenderNode<S>,
+    ) -> EventResponse {
+        let response = self.capture_recursive(event.clone(), render_root);
+        if response.is_handled() {
+            return response;
+        }
+        self.bubble_recursive(event, render_root)
+    }
+
+    fn capture_recursive(&mut self, event: event::Event, node: &RenderNode<S>) -> EventResponse {
+        match node {
             RenderNode::SingleChild {
-                offset: _,
-                size: _,
-                renderer,
-                child,
+                renderer, child, ..
             } => {
-                if renderer.event_handler(&mut self.state, event.clone()) {
-                    return true;
+                let response = renderer.capture_event_handler(&mut self.state, event.clone());
+                if response.is_handled() {
+                    return response;
                 }
-                self.handle_event_recursive(event, child)
+                self.capture_recursive(event, child)
             }
-            RenderNode::MultiChild {
-                offset: _,
-                size: _,
-                child,
-            } => child
+            RenderNode::MultiChild { child, .. } => child
                 .iter()
-                .any(|c| self.handle_event_recursive(event.clone(), c)),
-            RenderNode::Leaf => false,
+                .map(|c| self.capture_recursive(event.clone(), c))
+                .find(|response| response.is_handled())
+                .unwrap_or_default(),
+            RenderNode::Leaf => EventResponse::Ignored,
         }
     }
 
-    fn paint(node: &RenderNode<S>, target: &mut GraphicOperationQueue, origin_offset: Point) {
+    fn bubble_recursive(&mut self, event: event::Event, node: &RenderNode<S>) -> EventResponse {
         match node {
             RenderNode::SingleChild {
-                offset,
-                size,
-                renderer,
-                child,
+                renderer, child, ..
             } => {
-                let new_offset = origin_offset + offset.clone();
-                renderer.paint(*size, new_offset, target);
-                Self::paint(child, target, new_offset);
+                let response = self.bubble_recursive(event.clone(), child);
+                if response.is_handled() {
+                    return response;
+                }
+                renderer.event_handler(&mut self.state, event)
             }
-            RenderNode::MultiChild {
+            RenderNode::MultiChild { child, .. } => child
+                .iter()
+                .map(|c| self.bubble_recursive(event.clone(), c))
+                .find(|response| response.is_handled())
+                .unwrap_or_default(),
+            RenderNode::Leaf => EventResponse::Ignored,
+        }
+    }
+
+    /// Sends the `Tick` to the elements wanting it.
+    fn tick_elements(&mut self, event: event::Event) -> EventResponse {
+        let tickers = self.tickers.clone();
+        tickers
+            .iter()
+            .fold(EventResponse::Ignored, |response, element| {
+                element
+                    .capture_event_handler(&mut self.state, event.clone())
+                    .max(element.event_handler(&mut self.state, event.clone()))
+                    .max(response)
+            })
+    }
+
+    /// Sends the event to every element of the tree, whether they handle
+    /// it or not. The response is the strongest of theirs.
+    fn broadcast(&mut self, event: event::Event, node: &RenderNode<S>) -> EventResponse {
+        match node {
+            RenderNode::SingleChild {
+                renderer, child, ..
+            } => {
+                let response = renderer
+                    .capture_event_handler(&mut self.state, event.clone())
+                    .max(renderer.event_handler(&mut self.state, event.clone()));
+                self.broadcast(event, child).max(response)
+            }
+            RenderNode::MultiChild { child, .. } => {
+                child.iter().fold(EventResponse::Ignored, |response, c| {
+                    self.broadcast(event.clone(), c).max(response)
+                })
+            }
+            RenderNode::Leaf => EventResponse::Ignored,
+        }
+    }
+
+    /// Dispatches the event to the element at the end of `path`: capture
+    /// from the root to the target, then target and bubble back to the root.
+    fn dispatch_along(
+        &mut self,
+        event: event::Event,
+        root: &RenderNode<S>,
+        path: &[usize],
+    ) -> EventResponse {
+        let elements = elements_along(root, path);
+        for (element, origin) in &elements {
+            let response =
+                element.capture_event_handler(&mut self.state, event.relative_to(*origin));
+            if response.is_handled() {
+                return response;
+            }
+        }
+        for (element, origin) in elements.iter().rev() {
+            let response = element.event_handler(&mut self.state, event.relative_to(*origin));
+            if response.is_handled() {
+                return response;
+            }
+        }
+        EventResponse::Ignored
+    }
+
+    /// Gives the focus to the deepest focusable element on `path`.
+    fn focus_path(&mut self, root: &RenderNode<S>, path: &[usize]) -> bool {
+        let index = focus::focusables(root)
+            .iter()
+            .rposition(|focusable| path.starts_with(&focusable.path));
+        match index {
+            Some(index) if self.focus != Some(index) => {
+                self.focus = Some(index);
+                true
+            }
+            _ => false,
+        }
+    }
+
+    fn move_focus(&mut self, event: &event::Event) -> bool {
+        // an encoder has no direction on screen, so it moves in render order
+        let (current, direction, traversal) = match (self.focus, event) {
+            (Some(current), Event::DirectionPressed(direction)) => {
+                (current, *direction, self.focus_traversal)
+            }
+            (Some(current), Event::EncoderRotated(steps)) if *steps > 0 => {
+                (current, Direction::Down, FocusTraversal::Ordered)
+            }
+            (Some(current), Event::EncoderRotated(steps)) if *steps < 0 => {
+                (current, Direction::Up, FocusTraversal::Ordered)
+            }
+            _ => return false,
+        };
+        let focusables = focus::focusables(&self.last_render_tree);
+        match focus::next_focus(&focusables, current, &direction, traversal) {
+            Some(next) => {
+                self.focus = Some(next);
+                true
+            }
+            None => false,
+        }
+    }
+}
+
+fn collect_tickers<S: State>(node: &RenderNode<S>, tickers: &mut Vec<Element<S>>) {
+    match node {
+        RenderNode::SingleChild {
+            renderer, child, ..
+        } => {
+            if renderer.wants_ticks() {
+                tickers.push(renderer.clone());
+            }
+            collect_tickers(child, tickers);
+        }
+        RenderNode::MultiChild { child, .. } => {
+            for c in child {
+                collect_tickers(c, tickers);
+            }
+        }
+        RenderNode::Leaf => {}
+    }
+}
+
+/// Elements rendering the nodes from the root to the end of `path`, with
+/// their absolute position.
+fn elements_along<S: State>(root: &RenderNode<S>, path: &[usize]) -> Vec<(Element<S>, Point)> {
+    let mut elements = Vec::new();
+    let mut node = root;
+    let mut origin = Point::zero();
+    let mut path = path.iter();
+    loop {
+        node = match node {
+            RenderNode::SingleChild {
                 offset,
-                size: _,
+                renderer,
                 child,
+                ..
             } => {
-                let new_offset = origin_offset + offset.clone();
-                for item in child {
-                    Self::paint(item, target, new_offset);
+                origin += *offset;
+                elements.push((renderer.clone(), origin));
+                match path.next() {
+                    Some(_) => child,
+                    None => break,
                 }
             }
-            RenderNode::Leaf => {}
+            RenderNode::MultiChild { offset, child, .. } => match path.next() {
+                Some(index) => {
+                    origin += *offset;
+                    &child[*index]
+                }
+                None => break,
+            },
+            RenderNode::Leaf => break,
         }
     }
+    elements
 }
 
 impl<S: State, U: GraphicsEndpoint> Runner for App<S, U> {
@@ -326,28 +756,80 @@ impl<S: State, U: GraphicsEndpoint> Runner for App<S, U> {
     }
 
     fn handle_event(&mut self, event: event::Event) {
+        let response = self.run_shortcuts(Phase::Capture, &event);
+        if response.is_handled() {
+            if response.is_changed() {
+                self.render();
+            }
+            return;
+        }
+
         let mut swap_tree = RenderNode::Leaf;
         core::mem::swap(&mut swap_tree, &mut self.last_render_tree);
-        if self.handle_event_recursive(event.clone(), &swap_tree) {
-            self.last_render_tree = self
-                .root
-                .render(
-                    Constraints {
-                        min: Size::zero(),
-                        max: self.inital_size,
-                    },
-                    &self.state,
-                )
-                .1;
-        } else {
-            core::mem::swap(&mut swap_tree, &mut self.last_render_tree);
-            println!("Unhandled event: {:?}", event);
+        let mut focus_moved = false;
+        let response = match event.position() {
+            // time events aren't aimed at any element
+            None if matches!(event, Event::Tick(_)) => self.tick_elements(event.clone()),
+            None if matches!(event, Event::Timer(_)) => self.broadcast(event.clone(), &swap_tree),
+            // pointer events go to the deepest element under the point
+            Some(point) => match pointer::hit_test(&swap_tree, point) {
+                Some(path) => {
+                    if let Event::PointerDown(_) = event {
+                        focus_moved = self.focus_path(&swap_tree, &path);
+                    }
+                    self.dispatch_along(event.clone(), &swap_tree, &path)
+                }
+                None => EventResponse::Ignored,
+            },
+            // key events only go through the focused path when there is one
+            None => match self.focus {
+                Some(index) => {
+                    let path = focus::focusables(&swap_tree).swap_remove(index).path;
+                    self.dispatch_along(event.clone(), &swap_tree, &path)
+                }
+                None => self.handle_event_recursive(event.clone(), &swap_tree),
+            },
+        };
+        core::mem::swap(&mut swap_tree, &mut self.last_render_tree);
+
+        let response = match response {
+            EventResponse::Ignored => self.run_shortcuts(Phase::Bubble, &event),
+            response => response,
+        };
+        // handled events that left the state as it was don't need a render
+        if response.is_changed()
+            || focus_moved
+            || (!response.is_handled() && self.move_focus(&event))
+        {
+            self.render();
         }
     }
 
+    /// Sends the repeats of the held keys, the posts of other threads, the
+    /// timers due and a `Tick`.
+    fn tick(&mut self, now: Duration) {
+        self.poll_input(now);
+        self.handle_posted();
+        let fired = match self.state.timers() {
+            Some(timers) => timers.advance(now),
+            None => Vec::new(),
+        };
+        for id in fired {
+            self.handle_event(Event::Timer(id));
+        }
+        self.handle_event(Event::Tick(now));
+    }
+
     fn draw(&mut self) {
+        if !core::mem::take(&mut self.needs_draw) {
+            return;
+        }
         let mut queue = GraphicOperationQueue::new();
-        Self::paint(&self.last_render_tree, &mut queue, Point::new(0, 0));
+        self.last_render_tree.paint(&mut queue, Point::zero());
+        self.optimization_stats = match self.optimize {
+            true => queue.optimize(),
+            false => OptimizationStats::default(),
+        };
         self.endpoint.draw_queue(queue);
     }
 }
@@ -0,0 +1,183 @@
+//! Screen history of an app: a [`Navigator`] kept in the state holds the
+//! stack of routes, and a [`Router`](crate::elements::Router) shows the
+//! screen of the current one.
+
+use core::time::Duration;
+
+/// How the current route was reached.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Navigation {
+    Push,
+    Pop,
+    Replace,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+struct Transition {
+    navigation: Navigation,
+    // set by the first tick after the navigation
+    started: Option<Duration>,
+    progress: f32,
+}
+
+/// Stack of routes, whose last one is the current screen. The first route
+/// is the root, which is never popped.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Navigator<R> {
+    routes: Vec<R>,
+    // unique for every route pushed, so the app can keep a focus per route
+    ids: Vec<u64>,
+    next_id: u64,
+    transition: Option<Transition>,
+}
+
+impl<R: Default> Default for Navigator<R> {
+    fn default() -> Self {
+        Self::new(R::default())
+    }
+}
+
+impl<R> Navigator<R> {
+    pub fn new(root: R) -> Self {
+        Self {
+            routes: vec![root],
+            ids: vec![0],
+            next_id: 1,
+            transition: None,
+        }
+    }
+
+    pub fn current(&self) -> &R {
+        self.routes.last().expect("the root route is never popped")
+    }
+
+    /// The routes from the root to the current one.
+    pub fn routes(&self) -> &[R] {
+        &self.routes
+    }
+
+    pub fn can_pop(&self) -> bool {
+        self.routes.len() > 1
+    }
+
+    pub fn push(&mut self, route: R) {
+        self.routes.push(route);
+        let id = self.new_id();
+        self.ids.push(id);
+        self.start(Navigation::Push);
+    }
+
+    /// Goes back to the previous route, returning the current one, unless
+    /// it is the root.
+    pub fn pop(&mut self) -> Option<R> {
+        if !self.can_pop() {
+            return None;
+        }
+        self.start(Navigation::Pop);
+        self.ids.pop();
+        self.routes.pop()
+    }
+
+    /// Replaces the current route, returning it.
+    pub fn replace(&mut self, route: R) -> R {
+        self.start(Navigation::Replace);
+        let last = self.routes.len() - 1;
+        self.ids[last] = self.new_id();
+        core::mem::replace(&mut self.routes[last], route)
+    }
+
+    /// Pops every route but the root.
+    pub fn pop_to_root(&mut self) {
+        if self.can_pop() {
+            self.routes.truncate(1);
+            self.ids.truncate(1);
+            self.start(Navigation::Pop);
+        }
+    }
+
+    /// The last navigation and its progress from 0 to 1, until a sliding
+    /// router finishes animating it.
+    pub fn transition(&self) -> Option<(Navigation, f32)> {
+        self.transition
+            .map(|transition| (transition.navigation, transition.progress))
+    }
+
+    /// Identifies the current route, as opposed to an equal one pushed
+    /// later.
+    pub(crate) fn current_id(&self) -> u64 {
+        self.ids[self.ids.len() - 1]
+    }
+
+    fn new_id(&mut self) -> u64 {
+        self.next_id += 1;
+        self.next_id - 1
+    }
+
+    fn start(&mut self, navigation: Navigation) {
+        self.transition = Some(Transition {
+            navigation,
+            started: None,
+            progress: 0.0,
+        });
+    }
+
+    /// Moves the transition to `now`, returning whether it progressed.
+    pub(crate) fn animate(&mut self, now: Duration, duration: Duration) -> bool {
+        let Some(transition) = &mut self.transition else {
+            return false;
+        };
+        let started = *transition.started.get_or_insert(now);
+        let elapsed = now.saturating_sub(started);
+        if elapsed >= duration {
+            self.transition = None;
+        } else {
+            transition.progress = elapsed.as_secs_f32() / duration.as_secs_f32();
+        }
+        true
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[derive(Clone, Copy, Debug, Default, PartialEq)]
+    enum Route {
+        #[default]
+        Home,
+        Settings,
+        About,
+    }
+
+    #[test]
+    fn the_root_is_never_popped() {
+        let mut navigator = Navigator::<Route>::default();
+        navigator.push(Route::Settings);
+        assert_eq!(navigator.replace(Route::About), Route::Settings);
+        assert_eq!(navigator.routes(), &[Route::Home, Route::About]);
+
+        assert_eq!(navigator.pop(), Some(Route::About));
+        assert_eq!(navigator.pop(), None);
+        assert_eq!(navigator.current(), &Route::Home);
+
+        navigator.push(Route::Settings);
+        navigator.push(Route::About);
+        navigator.pop_to_root();
+        assert_eq!(navigator.routes(), &[Route::Home]);
+    }
+
+    #[test]
+    fn transitions_start_on_the_first_tick() {
+        let ms = Duration::from_millis;
+        let mut navigator = Navigator::new(Route::Home);
+        navigator.push(Route::About);
+        assert_eq!(navigator.transition(), Some((Navigation::Push, 0.0)));
+
+        assert!(navigator.animate(ms(1000), ms(200)));
+        assert!(navigator.animate(ms(1050), ms(200)));
+        assert_eq!(navigator.transition(), Some((Navigation::Push, 0.25)));
+        assert!(navigator.animate(ms(1200), ms(200)));
+        assert_eq!(navigator.transition(), None);
+        assert!(!navigator.animate(ms(1250), ms(200)));
+    }
+}
@@ -0,0 +1,113 @@
+mod gesture;
+
+pub use gesture::*;
+
+use crate::defs::{RenderNode, State};
+use crate::utils::*;
+
+/// Path to the deepest element of the tree containing `point`, in the same
+/// form as [`crate::focus`] paths, or `None` when no element contains it.
+///
+/// Later children are painted over the earlier ones, so they are hit first.
+pub(crate) fn hit_test<S: State>(root: &RenderNode<S>, point: Point) -> Option<Vec<usize>> {
+    let mut path = Vec::new();
+    hit(root, point, Point::zero(), &mut path).then_some(path)
+}
+
+fn hit<S: State>(node: &RenderNode<S>, point: Point, origin: Point, path: &mut Vec<usize>) -> bool {
+    match node {
+        RenderNode::SingleChild {
+            offset,
+            size,
+            child,
+            ..
+        } => {
+            let origin = origin + *offset;
+            if !Rectangle::new(origin, *size).contains(point) {
+                return false;
+            }
+            path.push(0);
+            if !hit(child, point, origin, path) {
+                path.pop();
+            }
+            true
+        }
+        RenderNode::MultiChild { offset, child, .. } => {
+            let origin = origin + *offset;
+            child.iter().enumerate().rev().any(|(index, item)| {
+                path.push(index);
+                let found = hit(item, point, origin, path);
+                if !found {
+                    path.pop();
+                }
+                found
+            })
+        }
+        RenderNode::Leaf => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::defs::{Element, Runner, State};
+    use crate::graphics::RecordingEndpoint;
+    use crate::utils::*;
+    use crate::{elements, lens, App, ItemSelector, ItemSelectorState, Text};
+
+    #[derive(Default)]
+    struct List {
+        items: Vec<&'static str>,
+        list: ItemSelectorState,
+        outside: usize,
+    }
+
+    impl State for List {}
+
+    fn item(text: &&'static str, _active: bool) -> Element<List> {
+        Text::new(text.to_string())
+    }
+
+    fn count_outside(state: &mut List, event: Event) -> bool {
+        if let Event::PointerDown(_) = event {
+            state.outside += 1;
+            return true;
+        }
+        false
+    }
+
+    fn list(_: &List) -> Element<List> {
+        elements::Handler::new(
+            count_outside,
+            elements::padding(
+                EdgeInsets::all(10),
+                ItemSelector::new(lens!(List, items), lens!(List, list), item),
+            ),
+        )
+    }
+
+    fn app() -> App<List, RecordingEndpoint> {
+        let mut app = App::new(list, Size::new(100, 100), RecordingEndpoint::new());
+        app.state.items = vec!["a", "b", "c"];
+        app.render();
+        app
+    }
+
+    #[test]
+    fn tapping_an_item_selects_it() {
+        let mut app = app();
+        app.handle_event(Event::PointerDown(Point::new(12, 55)));
+        assert_eq!(app.state().list.active, 2);
+        assert_eq!(app.state().list.selected, None);
+        app.handle_event(Event::PointerUp(Point::new(14, 60)));
+        assert_eq!(app.state().list.selected, Some(2));
+        assert_eq!(app.state().outside, 0);
+    }
+
+    #[test]
+    fn taps_outside_the_items_bubble_up() {
+        let mut app = app();
+        app.handle_event(Event::PointerDown(Point::new(2, 2)));
+        assert_eq!(app.state().outside, 1);
+        assert_eq!(app.state().list.active, 0);
+    }
+}
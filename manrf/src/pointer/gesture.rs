@@ -0,0 +1,311 @@
+use core::time::Duration;
+
+use crate::utils::*;
+
+/// A gesture recognized from a sequence of pointer events.
+///
+/// Positions are where the gesture started, which is also where it is hit
+/// tested.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Gesture {
+    Tap(Point),
+    /// Sent instead of the second of two quick taps at the same place.
+    DoubleTap(Point),
+    /// Sent while the pointer is still down; no tap follows it.
+    LongPress(Point),
+    Swipe {
+        from: Point,
+        direction: Direction,
+        /// Pixels per second along the direction.
+        velocity: u32,
+    },
+}
+
+impl Gesture {
+    pub fn position(&self) -> Point {
+        match self {
+            Gesture::Tap(point) | Gesture::DoubleTap(point) | Gesture::LongPress(point) => *point,
+            Gesture::Swipe { from, .. } => *from,
+        }
+    }
+
+    pub(crate) fn relative_to(&self, origin: Point) -> Gesture {
+        match *self {
+            Gesture::Tap(point) => Gesture::Tap(point - origin),
+            Gesture::DoubleTap(point) => Gesture::DoubleTap(point - origin),
+            Gesture::LongPress(point) => Gesture::LongPress(point - origin),
+            Gesture::Swipe {
+                from,
+                direction,
+                velocity,
+            } => Gesture::Swipe {
+                from: from - origin,
+                direction,
+                velocity,
+            },
+        }
+    }
+}
+
+/// Thresholds used by [`GestureRecognizer`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct GestureConfig {
+    /// How far, in pixels, the pointer can move and still tap.
+    pub tap_slop: u32,
+    /// Maximum time between two taps of a double tap.
+    pub double_tap: Duration,
+    /// How long the pointer must stay down, without moving, to long press.
+    pub long_press: Duration,
+    /// Minimum distance of a swipe, in pixels.
+    pub swipe_distance: u32,
+    /// Minimum velocity of a swipe, in pixels per second.
+    pub swipe_velocity: u32,
+}
+
+impl Default for GestureConfig {
+    fn default() -> Self {
+        Self {
+            tap_slop: 6,
+            double_tap: Duration::from_millis(300),
+            long_press: Duration::from_millis(500),
+            swipe_distance: 20,
+            swipe_velocity: 100,
+        }
+    }
+}
+
+struct Press {
+    start: Point,
+    since: Duration,
+    moved: bool,
+    long_pressed: bool,
+}
+
+/// Turns pointer events into gestures.
+///
+/// Timestamps follow the same rules as [`crate::event::InputTracker`].
+#[derive(Default)]
+pub struct GestureRecognizer {
+    config: GestureConfig,
+    press: Option<Press>,
+    last_tap: Option<(Point, Duration)>,
+}
+
+impl GestureRecognizer {
+    pub fn new(config: GestureConfig) -> Self {
+        Self {
+            config,
+            ..Self::default()
+        }
+    }
+
+    pub fn config(&self) -> GestureConfig {
+        self.config
+    }
+
+    pub fn set_config(&mut self, config: GestureConfig) {
+        self.config = config;
+    }
+
+    /// Follows a pointer event, returning the gesture it completes.
+    pub fn process(&mut self, event: &Event, now: Duration) -> Option<Gesture> {
+        match *event {
+            Event::PointerDown(point) => {
+                self.press = Some(Press {
+                    start: point,
+                    since: now,
+                    moved: false,
+                    long_pressed: false,
+                });
+                None
+            }
+            Event::PointerMove(point) => {
+                let slop = self.config.tap_slop;
+                if let Some(press) = self.press.as_mut() {
+                    press.moved |= distance(press.start, point) > slop;
+                }
+                None
+            }
+            Event::PointerUp(point) => {
+                let press = self.press.take()?;
+                if press.long_pressed {
+                    return None;
+                }
+                if !press.moved && distance(press.start, point) <= self.config.tap_slop {
+                    return Some(self.tap(press.start, now));
+                }
+                self.swipe(&press, point, now)
+            }
+            _ => None,
+        }
+    }
+
+    /// The long press due at `now`, if the pointer is held still.
+    pub fn poll(&mut self, now: Duration) -> Option<Gesture> {
+        let press = self.press.as_mut()?;
+        if press.moved || press.long_pressed || now < press.since + self.config.long_press {
+            return None;
+        }
+        press.long_pressed = true;
+        self.last_tap = None;
+        Some(Gesture::LongPress(press.start))
+    }
+
+    fn tap(&mut self, point: Point, now: Duration) -> Gesture {
+        match self.last_tap.take() {
+            Some((last, at))
+                if now <= at + self.config.double_tap
+                    && distance(last, point) <= self.config.tap_slop =>
+            {
+                Gesture::DoubleTap(point)
+            }
+            _ => {
+                self.last_tap = Some((point, now));
+                Gesture::Tap(point)
+            }
+        }
+    }
+
+    fn swipe(&self, press: &Press, end: Point, now: Duration) -> Option<Gesture> {
+        let delta = end - press.start;
+        let (direction, along) = if delta.x.abs() >= delta.y.abs() {
+            match delta.x > 0 {
+                true => (Direction::Right, delta.x.unsigned_abs()),
+                false => (Direction::Left, delta.x.unsigned_abs()),
+            }
+        } else {
+            match delta.y > 0 {
+                true => (Direction::Down, delta.y.unsigned_abs()),
+                false => (Direction::Up, delta.y.unsigned_abs()),
+            }
+        };
+        let millis = (now - press.since).as_millis().max(1) as u64;
+        let velocity = (along as u64 * 1000 / millis) as u32;
+        (along >= self.config.swipe_distance && velocity >= self.config.swipe_velocity).then_some(
+            Gesture::Swipe {
+                from: press.start,
+                direction,
+                velocity,
+            },
+        )
+    }
+}
+
+fn distance(a: Point, b: Point) -> u32 {
+    let delta = b - a;
+    delta.x.unsigned_abs().max(delta.y.unsigned_abs())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::defs::{Element, State};
+    use crate::elements::Handler;
+    use crate::graphics::RecordingEndpoint;
+    use crate::{App, Stack, Text};
+
+    fn ms(millis: u64) -> Duration {
+        Duration::from_millis(millis)
+    }
+
+    fn press(
+        recognizer: &mut GestureRecognizer,
+        from: Point,
+        to: Point,
+        at: u64,
+        held: u64,
+    ) -> Option<Gesture> {
+        recognizer.process(&Event::PointerDown(from), ms(at));
+        recognizer.process(&Event::PointerMove(to), ms(at + held / 2));
+        recognizer.process(&Event::PointerUp(to), ms(at + held))
+    }
+
+    #[test]
+    fn quick_taps_make_a_double_tap() {
+        let mut recognizer = GestureRecognizer::default();
+        let point = Point::new(10, 10);
+        let near = Point::new(12, 9);
+        assert_eq!(
+            press(&mut recognizer, point, near, 0, 50),
+            Some(Gesture::Tap(point))
+        );
+        assert_eq!(
+            press(&mut recognizer, near, near, 200, 50),
+            Some(Gesture::DoubleTap(near))
+        );
+        assert_eq!(
+            press(&mut recognizer, near, near, 1000, 50),
+            Some(Gesture::Tap(near))
+        );
+    }
+
+    #[test]
+    fn held_pointer_long_presses_once() {
+        let mut recognizer = GestureRecognizer::default();
+        let point = Point::new(10, 10);
+        recognizer.process(&Event::PointerDown(point), ms(0));
+        assert_eq!(recognizer.poll(ms(499)), None);
+        assert_eq!(recognizer.poll(ms(500)), Some(Gesture::LongPress(point)));
+        assert_eq!(recognizer.poll(ms(900)), None);
+        assert_eq!(recognizer.process(&Event::PointerUp(point), ms(1000)), None);
+    }
+
+    #[test]
+    fn fast_moves_swipe() {
+        let mut recognizer = GestureRecognizer::default();
+        let from = Point::new(100, 50);
+        assert_eq!(
+            press(&mut recognizer, from, Point::new(40, 60), 0, 200),
+            Some(Gesture::Swipe {
+                from,
+                direction: Direction::Left,
+                velocity: 300,
+            })
+        );
+        // too slow to be a swipe, and too far to be a tap
+        assert_eq!(
+            press(&mut recognizer, from, Point::new(100, 90), 0, 1000),
+            None
+        );
+    }
+
+    #[derive(Default)]
+    struct Pages {
+        page: usize,
+    }
+
+    impl State for Pages {}
+
+    fn pager(state: &mut Pages, event: Event) -> bool {
+        match event {
+            Event::Gesture(Gesture::Swipe {
+                direction: Direction::Left,
+                ..
+            }) => {
+                state.page += 1;
+                true
+            }
+            _ => false,
+        }
+    }
+
+    fn pages(state: &Pages) -> Element<Pages> {
+        Stack::col(vec![
+            Text::new("Title".to_string()) as Element<Pages>,
+            Handler::new(pager, Text::new(format!("Page {}", state.page))),
+        ])
+    }
+
+    #[test]
+    fn swipes_go_to_the_element_where_they_started() {
+        let mut app = App::new(pages, Size::new(100, 100), RecordingEndpoint::new());
+        app.handle_input(Event::PointerDown(Point::new(30, 15)), ms(0));
+        app.handle_input(Event::PointerUp(Point::new(0, 15)), ms(100));
+        assert_eq!(app.state().page, 1);
+
+        // starting on the title
+        app.handle_input(Event::PointerDown(Point::new(30, 5)), ms(1000));
+        app.handle_input(Event::PointerUp(Point::new(0, 15)), ms(1100));
+        assert_eq!(app.state().page, 1);
+    }
+}
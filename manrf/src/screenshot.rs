@@ -0,0 +1,164 @@
+use std::path::{Path, PathBuf};
+
+use embedded_graphics::prelude::{Dimensions, OriginDimensions, PointsIter};
+use embedded_graphics_simulator::{OutputSettings, OutputSettingsBuilder, SimulatorDisplay};
+
+use crate::defs::{Element, State};
+use crate::graphics::EmbeddedGraphicsEndpoint;
+use crate::utils::*;
+use crate::App;
+
+/// Environment variable that makes `assert_screenshot` overwrite the stored
+/// images instead of comparing against them.
+pub const UPDATE_SCREENSHOTS_ENV: &str = "MANRF_UPDATE_SCREENSHOTS";
+
+pub type OffscreenDisplay = SimulatorDisplay<Rgb888>;
+
+pub type ScreenshotEndpoint = EmbeddedGraphicsEndpoint<OffscreenDisplay>;
+
+impl ScreenshotEndpoint {
+    /// Creates an endpoint drawing into an in-memory buffer of the given size.
+    pub fn offscreen(size: Size) -> Self {
+        Self::new(OffscreenDisplay::new(size))
+    }
+}
+
+/// Creates an app rendering offscreen, no window or display is needed.
+pub fn offscreen_app<S: State>(
+    size: Size,
+    root: impl Fn(&S) -> Element<S> + 'static,
+) -> App<S, ScreenshotEndpoint> {
+    App::new(root, size, ScreenshotEndpoint::offscreen(size))
+}
+
+fn output_settings() -> OutputSettings {
+    OutputSettingsBuilder::new()
+        .scale(1)
+        .pixel_spacing(0)
+        .build()
+}
+
+pub fn save_png(display: &OffscreenDisplay, path: impl AsRef<Path>) {
+    let path = path.as_ref();
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent).expect("can't create the screenshot directory");
+    }
+    display
+        .to_rgb_output_image(&output_settings())
+        .save_png(path)
+        .unwrap_or_else(|error| panic!("can't save {}: {}", path.display(), error));
+}
+
+/// Builds an image with the expected, actual and difference images side by
+/// side, or `None` when both displays are equal.
+///
+/// In the difference image the equal pixels are drawn as a dimmed gray
+/// version of the expected image and the different ones in red.
+pub fn diff_image(
+    expected: &OffscreenDisplay,
+    actual: &OffscreenDisplay,
+) -> Option<OffscreenDisplay> {
+    expected.diff(actual)?;
+
+    let size = expected.size();
+    let mut output = OffscreenDisplay::new(Size::new(size.width * 3, size.height));
+    for point in expected.bounding_box().points() {
+        let expected_color = expected.get_pixel(point);
+        let actual_color = actual.get_pixel(point);
+        let diff_color = if expected_color == actual_color {
+            let luma =
+                (expected_color.r() as u32 + expected_color.g() as u32 + expected_color.b() as u32)
+                    / 3;
+            let dimmed = (luma / 3) as u8;
+            Rgb888::new(dimmed, dimmed, dimmed)
+        } else {
+            Rgb888::RED
+        };
+        let _ = output.draw_iter([
+            embedded_graphics::Pixel(point, expected_color),
+            embedded_graphics::Pixel(point + Point::new(size.width as i32, 0), actual_color),
+            embedded_graphics::Pixel(point + Point::new(size.width as i32 * 2, 0), diff_color),
+        ]);
+    }
+    Some(output)
+}
+
+fn sibling_path(path: &Path, suffix: &str) -> PathBuf {
+    let stem = path.file_stem().unwrap_or_default().to_string_lossy();
+    path.with_file_name(format!("{}.{}.png", stem, suffix))
+}
+
+/// Compares the display content against the PNG stored at `path`.
+///
+/// When `MANRF_UPDATE_SCREENSHOTS` is set, the image is written instead. A
+/// missing image fails like a mismatch: `<name>.actual.png` and, when there
+/// is an expected image, `<name>.diff.png` are written next to it and the
+/// call panics.
+pub fn assert_screenshot(display: &OffscreenDisplay, path: impl AsRef<Path>) {
+    let path = path.as_ref();
+    if std::env::var_os(UPDATE_SCREENSHOTS_ENV).is_some() {
+        save_png(display, path);
+        return;
+    }
+    if !path.exists() {
+        let actual_path = sibling_path(path, "actual");
+        save_png(display, &actual_path);
+        panic!(
+            "screenshot {} doesn't exist, see {} and run with {} set to create it",
+            path.display(),
+            actual_path.display(),
+            UPDATE_SCREENSHOTS_ENV
+        );
+    }
+
+    let expected = OffscreenDisplay::load_png(path)
+        .unwrap_or_else(|error| panic!("can't load {}: {}", path.display(), error));
+    if expected.size() != display.size() {
+        save_png(display, sibling_path(path, "actual"));
+        panic!(
+            "screenshot {} has size {}x{} but the display is {}x{}",
+            path.display(),
+            expected.size().width,
+            expected.size().height,
+            display.size().width,
+            display.size().height
+        );
+    }
+
+    if let Some(diff) = diff_image(&expected, display) {
+        let different = expected
+            .bounding_box()
+            .points()
+            .filter(|point| expected.get_pixel(*point) != display.get_pixel(*point))
+            .count();
+        let actual_path = sibling_path(path, "actual");
+        let diff_path = sibling_path(path, "diff");
+        save_png(display, &actual_path);
+        save_png(&diff, &diff_path);
+        panic!(
+            "screenshot {} differs in {} pixels, see {} and {}",
+            path.display(),
+            different,
+            actual_path.display(),
+            diff_path.display()
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn missing_screenshots_fail() {
+        if std::env::var_os(UPDATE_SCREENSHOTS_ENV).is_some() {
+            return;
+        }
+        let path = std::env::temp_dir().join("manrf_missing_screenshot.png");
+        let _ = std::fs::remove_file(&path);
+        let display = OffscreenDisplay::new(Size::new(4, 4));
+        let result = std::panic::catch_unwind(|| assert_screenshot(&display, &path));
+        assert!(result.is_err());
+        assert!(!path.exists());
+    }
+}
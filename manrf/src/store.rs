@@ -0,0 +1,284 @@
+//! Elm style state: the elements emit messages, and a single
+//! [`Update::update`] applies them to the model.
+//!
+//! An app using it has a [`Store`] of its model as state. Every message
+//! goes through [`Store::dispatch`], so they can all be observed with
+//! [`App::subscribe`] for logging and tests.
+//!
+//! Stateful widgets, like an [`ItemSelector`](crate::ItemSelector), keep
+//! their own state in the model and change it without messages, through
+//! lenses starting with [`Model`].
+
+use core::ops::Deref;
+
+use crate::defs::{Element, State};
+use crate::elements::Handler;
+use crate::event::{Disconnected, Event, EventSender, Timers};
+use crate::graphics::GraphicsEndpoint;
+use crate::lens::Lens;
+use crate::App;
+
+/// Model of an app changed only by its messages.
+pub trait Update: State {
+    type Message: 'static;
+
+    fn update(&mut self, message: Self::Message);
+}
+
+type Observer<M> = Box<dyn FnMut(&M)>;
+
+/// State of an app holding its model, which is read through `Deref` and
+/// changed by dispatching messages.
+pub struct Store<M: Update> {
+    model: M,
+    observers: Vec<Observer<M::Message>>,
+}
+
+impl<M: Update> Default for Store<M> {
+    fn default() -> Self {
+        Self {
+            model: M::default(),
+            observers: Vec::new(),
+        }
+    }
+}
+
+impl<M: Update> State for Store<M> {
+    fn timers(&mut self) -> Option<&mut Timers> {
+        self.model.timers()
+    }
+}
+
+impl<M: Update> Deref for Store<M> {
+    type Target = M;
+
+    fn deref(&self) -> &M {
+        &self.model
+    }
+}
+
+/// Lens to the model of a [`Store`], composed with the lenses of the
+/// widget states it holds.
+///
+/// ```ignore
+/// ItemSelector::new(Model.then(lens!(Menu, items)), Model.then(lens!(Menu, selector)), item)
+/// ```
+pub struct Model;
+
+impl<M: Update> Lens<Store<M>, M> for Model {
+    fn get<'a>(&self, store: &'a Store<M>) -> &'a M {
+        &store.model
+    }
+
+    fn get_mut<'a>(&self, store: &'a mut Store<M>) -> &'a mut M {
+        &mut store.model
+    }
+}
+
+impl<M: Update> Store<M> {
+    pub fn model(&self) -> &M {
+        &self.model
+    }
+
+    /// Shows the message to the observers, then applies it to the model.
+    pub fn dispatch(&mut self, message: M::Message) {
+        for observer in &mut self.observers {
+            observer(&message);
+        }
+        self.model.update(message);
+    }
+
+    /// Calls `observer` with every message dispatched from now on, before
+    /// it is applied.
+    pub fn subscribe(&mut self, observer: impl FnMut(&M::Message) + 'static) {
+        self.observers.push(Box::new(observer));
+    }
+}
+
+/// Handles the events `message` maps to a message by dispatching it, after
+/// its descendants ignored them.
+pub fn emit<M: Update>(
+    message: impl Fn(Event) -> Option<M::Message> + 'static,
+    child: Element<Store<M>>,
+) -> Element<Store<M>> {
+    Handler::new(
+        move |store: &mut Store<M>, event| match message(event) {
+            Some(message) => {
+                store.dispatch(message);
+                true
+            }
+            None => false,
+        },
+        child,
+    )
+}
+
+/// App of the model `M`, whose elements can only emit an `M::Message`.
+pub type StoreApp<M, U> = App<Store<M>, U>;
+
+impl<M: Update, U: GraphicsEndpoint> StoreApp<M, U> {
+    /// Dispatches the message to the model, then renders the app again.
+    pub fn dispatch(&mut self, message: M::Message) {
+        self.state.dispatch(message);
+        self.render();
+    }
+
+    /// Calls `observer` with every message dispatched to the model from now
+    /// on, by the elements or by the app.
+    pub fn subscribe(&mut self, observer: impl FnMut(&M::Message) + 'static) {
+        self.state.subscribe(observer);
+    }
+}
+
+impl<M: Update> EventSender<Store<M>>
+where
+    M::Message: Send,
+{
+    /// Dispatches the message on the next tick of the app.
+    pub fn post_message(&self, message: M::Message) -> Result<(), Disconnected> {
+        self.post_update(move |store| store.dispatch(message))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::event::{Button, Direction};
+    use crate::graphics::RecordingEndpoint;
+    use crate::{lens, ItemSelector, ItemSelectorState, Runner, Text};
+    use core::time::Duration;
+    use embedded_graphics::prelude::Size;
+    use std::cell::RefCell;
+    use std::rc::Rc;
+
+    #[derive(Default)]
+    struct Counter {
+        count: i32,
+    }
+
+    impl State for Counter {}
+
+    #[derive(Clone, Debug, PartialEq)]
+    enum Message {
+        Increment,
+        Decrement,
+        Reset,
+    }
+
+    impl Update for Counter {
+        type Message = Message;
+
+        fn update(&mut self, message: Message) {
+            match message {
+                Message::Increment => self.count += 1,
+                Message::Decrement => self.count -= 1,
+                Message::Reset => self.count = 0,
+            }
+        }
+    }
+
+    fn counter(store: &Store<Counter>) -> Element<Store<Counter>> {
+        emit(
+            |event| match event {
+                Event::DirectionPressed(Direction::Up) => Some(Message::Increment),
+                Event::DirectionPressed(Direction::Down) => Some(Message::Decrement),
+                Event::ButtonPressed(Button::Back) => Some(Message::Reset),
+                _ => None,
+            },
+            Text::new(format!("Count {}", store.count)),
+        )
+    }
+
+    #[test]
+    fn update_is_tested_without_an_app() {
+        let mut model = Counter::default();
+        model.update(Message::Increment);
+        model.update(Message::Increment);
+        model.update(Message::Decrement);
+        assert_eq!(model.count, 1);
+    }
+
+    #[test]
+    fn dispatched_messages_are_observed_and_applied() {
+        let mut app = App::new(counter, Size::new(100, 100), RecordingEndpoint::new());
+        let log = Rc::new(RefCell::new(Vec::new()));
+        let observed = log.clone();
+        app.subscribe(move |message: &Message| observed.borrow_mut().push(message.clone()));
+
+        app.handle_event(Event::DirectionPressed(Direction::Up));
+        app.handle_event(Event::DirectionPressed(Direction::Up));
+        app.handle_event(Event::ButtonPressed(Button::Principal));
+        app.dispatch(Message::Decrement);
+        app.draw();
+
+        assert_eq!(
+            *log.borrow(),
+            vec![Message::Increment, Message::Increment, Message::Decrement]
+        );
+        assert_eq!(app.state().count, 1);
+        assert!(app.endpoint.find_text("Count 1").is_some());
+    }
+
+    #[test]
+    fn messages_are_posted_from_other_threads() {
+        let mut app = App::new(counter, Size::new(100, 100), RecordingEndpoint::new());
+        let sender = app.sender();
+        std::thread::spawn(move || sender.post_message(Message::Increment).unwrap())
+            .join()
+            .unwrap();
+        app.tick(Duration::from_millis(16));
+        assert_eq!(app.state().count, 1);
+    }
+
+    #[derive(Default)]
+    struct Menu {
+        items: Vec<&'static str>,
+        selector: ItemSelectorState,
+    }
+
+    impl State for Menu {}
+
+    enum MenuMessage {
+        Clear,
+    }
+
+    impl Update for Menu {
+        type Message = MenuMessage;
+
+        fn update(&mut self, message: MenuMessage) {
+            match message {
+                MenuMessage::Clear => self.selector.selected = None,
+            }
+        }
+    }
+
+    fn menu(_: &Store<Menu>) -> Element<Store<Menu>> {
+        emit(
+            |event| match event {
+                Event::ButtonPressed(Button::Back) => Some(MenuMessage::Clear),
+                _ => None,
+            },
+            ItemSelector::new(
+                Model.then(lens!(Menu, items)),
+                Model.then(lens!(Menu, selector)),
+                |item: &&'static str, _| Text::new(item.to_string()),
+            ),
+        )
+    }
+
+    #[test]
+    fn widgets_keep_their_state_in_the_model() {
+        let mut app: StoreApp<Menu, _> =
+            App::new(menu, Size::new(100, 100), RecordingEndpoint::new());
+        Model.get_mut(&mut app.state).items = vec!["a", "b"];
+        app.render();
+
+        app.handle_event(Event::DirectionPressed(Direction::Down));
+        app.handle_event(Event::ButtonPressed(Button::Principal));
+        assert_eq!(app.state().selector.selected, Some(1));
+
+        app.handle_event(Event::ButtonPressed(Button::Back));
+        assert_eq!(app.state().selector.selected, None);
+        assert_eq!(app.state().selector.active, 1);
+    }
+}
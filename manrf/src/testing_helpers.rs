@@ -1,25 +1,51 @@
-use std::time::SystemTime;
+use std::time::{Instant, SystemTime};
 
 use crate::defs::*;
 use crate::graphics::EmbeddedGraphicsEndpoint;
 use crate::utils::*;
-use embedded_graphics_simulator::{
-    OutputSettingsBuilder, SimulatorDisplay, SimulatorEvent, Window,
-};
+use embedded_graphics_simulator::{OutputSettingsBuilder, SimulatorDisplay, Window};
 
-use crate::{App, ComponentGenerator};
+use crate::event::simulator::SimulatorWindow;
+use crate::event::{InputSource, KeyBindings};
+use crate::App;
 
-type TINR = fn(Size, &mut dyn Runner);
+/// Path of a key bindings file replacing the default simulator bindings.
+const KEY_BINDINGS_ENV: &str = "MANRF_KEY_BINDINGS";
+
+type WindowCallback = fn(Size, &mut dyn Runner);
+
+pub type WindowEndpoint = EmbeddedGraphicsEndpoint<SimulatorDisplay<Rgb888>>;
+
+pub fn window_endpoint(size: Size) -> WindowEndpoint {
+    let display: SimulatorDisplay<Rgb888> = SimulatorDisplay::new(size);
+    EmbeddedGraphicsEndpoint::new(display)
+}
 
 #[allow(dead_code)]
-pub fn test_in_window<S: State>(size: Size, comp: ComponentGenerator<S>, callback: TINR) {
-    let display:SimulatorDisplay<Rgb888> = SimulatorDisplay::new(size);
+pub fn test_in_window<S: State>(
+    size: Size,
+    comp: impl Fn(&S) -> Element<S> + 'static,
+    callback: WindowCallback,
+) {
+    run_in_window(App::new(comp, size, window_endpoint(size)), callback);
+}
 
-    let endpoint = EmbeddedGraphicsEndpoint::new(display);
+/// Shows the app in a simulator window until it is closed.
+#[allow(dead_code)]
+pub fn run_in_window<S: State>(mut app: App<S, WindowEndpoint>, callback: WindowCallback) {
+    let size = app.inital_size;
 
-    let mut app = App::new(comp, size, endpoint);
+    let bindings = match std::env::var(KEY_BINDINGS_ENV) {
+        Ok(path) => {
+            let config = std::fs::read_to_string(&path).expect("can't read the key bindings");
+            KeyBindings::parse_simulator(&config)
+                .unwrap_or_else(|error| panic!("{}: {}", path, error))
+        }
+        Err(_) => KeyBindings::simulator(),
+    };
 
     let mut frames_counter = (SystemTime::now(), 0);
+    let started = Instant::now();
 
     let output_settings = OutputSettingsBuilder::new()
         .max_fps(60)
@@ -27,9 +53,10 @@ pub fn test_in_window<S: State>(size: Size, comp: ComponentGenerator<S>, callbac
         .scale(3)
         .build();
 
-    let mut window = Window::new("Hello World", &output_settings);
+    let mut window = SimulatorWindow::new(Window::new("Hello World", &output_settings), bindings);
 
-    'running: loop {
+    while !window.is_closed() {
+        app.tick(started.elapsed());
         app.draw();
         window.update(&app.endpoint.target);
 
@@ -39,17 +66,10 @@ pub fn test_in_window<S: State>(size: Size, comp: ComponentGenerator<S>, callbac
             frames_counter = (SystemTime::now(), 0);
         }
 
-        for event in window.events() {
-            match event {
-                SimulatorEvent::Quit => break 'running,
-                other => {
-                    let event = Event::try_from(other);
-                    if let Ok(event) = event {
-                        app.handle_event(event);
-                        callback(size, &mut app);
-                    }
-                }
-            }
+        let now = started.elapsed();
+        while let Some(event) = window.poll_event(now) {
+            app.handle_input(event, now);
+            callback(size, &mut app);
         }
     }
 }
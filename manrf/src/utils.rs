@@ -9,7 +9,7 @@ pub use embedded_graphics::pixelcolor::{Rgb565, Rgb888};
 pub use embedded_graphics::prelude::{Point, RgbColor};
 pub use embedded_graphics::primitives::Rectangle;
 pub use embedded_graphics::Drawable;
-pub use crate::event::{Button, Direction, Event};
+pub use crate::event::{Button, Direction, Event, EventResponse, Phase};
 
 pub use crate::utils::constraints::Constraints;
 pub use crate::utils::edge_insets::EdgeInsets;
\ No newline at end of file
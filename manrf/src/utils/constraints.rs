@@ -1,6 +1,6 @@
 use embedded_graphics::prelude::Size;
 
-#[derive(Copy, Clone)]
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
 pub struct Constraints {
     pub min: Size,
     pub max: Size,
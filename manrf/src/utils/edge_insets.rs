@@ -91,5 +91,15 @@ impl EdgeInsets {
     pub const fn top_left_offset(&self) -> Point {
         Point::new(self.left as i32, self.top as i32)
     }
+
+    /// The larger of both insets on every side.
+    pub fn max(&self, other: &EdgeInsets) -> Self {
+        EdgeInsets {
+            top: self.top.max(other.top),
+            right: self.right.max(other.right),
+            bottom: self.bottom.max(other.bottom),
+            left: self.left.max(other.left),
+        }
+    }
     
 }
\ No newline at end of file